@@ -0,0 +1,348 @@
+//! # Alert
+//!
+//! Notices what happened in a match between two consecutive snapshots of its details, so
+//! the user can be told about it even when looking at another match.
+//!
+//! Alerts can ring the terminal bell, flash the title of the tab and run a shell command.
+//! The command gets the details of the alert in these environment variables.
+//!
+//! | Variable              | Example                                                      |
+//! | --------------------- | ------------------------------------------------------------ |
+//! | `CRICKET_EVENT`       | `wicket`, `four`, `six`, `fifty`, `hundred`, `five_for` or `result` |
+//! | `CRICKET_MATCH_ID`    | `36096`                                                      |
+//! | `CRICKET_MATCH`       | `BAN vs SL`                                                  |
+//! | `CRICKET_DESCRIPTION` | `Mushfiqur Rahim reached 50 (60)`                            |
+//! | `CRICKET_PLAYER`      | `Mushfiqur Rahim`, empty for results                         |
+//! | `CRICKET_SCORE`       | `BAN 257/6 (48)`                                             |
+
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+use crate::cricbuzz_api::{CricbuzzJson, CricbuzzMiniscoreBatsman, CricbuzzMiniscoreBowler};
+
+/// What happened in a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    Wicket,
+    Four,
+    Six,
+    Fifty,
+    Hundred,
+    FiveFor,
+    Result,
+}
+
+/// Something that happened in a match
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub match_id: u32,
+    /// Short form of the teams playing the match. Eg. BAN vs SL
+    pub match_name: String,
+    /// Eg. "Mushfiqur Rahim reached 50 (60)"
+    pub description: String,
+    /// Player the alert is about, empty if it is about the match
+    pub player: String,
+    /// Score of the batting team. Eg. "BAN 257/6 (48)"
+    pub score: String,
+}
+
+impl AlertKind {
+    /// Returns the alert corresponding to its name in the config file
+    pub fn from_name(name: &str) -> Option<AlertKind> {
+        match name {
+            "wicket" => Some(AlertKind::Wicket),
+            "four" => Some(AlertKind::Four),
+            "six" => Some(AlertKind::Six),
+            "fifty" => Some(AlertKind::Fifty),
+            "hundred" => Some(AlertKind::Hundred),
+            "five_for" => Some(AlertKind::FiveFor),
+            "result" => Some(AlertKind::Result),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the alert used in the config file and the hook command
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertKind::Wicket => "wicket",
+            AlertKind::Four => "four",
+            AlertKind::Six => "six",
+            AlertKind::Fifty => "fifty",
+            AlertKind::Hundred => "hundred",
+            AlertKind::FiveFor => "five_for",
+            AlertKind::Result => "result",
+        }
+    }
+}
+
+/// Returns everything that happened in a match between two snapshots of its details
+///
+/// # Arguments
+///
+/// * `match_id` - Number used by the provider to identify the match
+/// * `match_name` - Short form of the teams playing the match
+/// * `prev` - Details of the match fetched before `curr`
+/// * `curr` - Latest details of the match
+pub fn detect_alerts(
+    match_id: u32,
+    match_name: &str,
+    prev: &CricbuzzJson,
+    curr: &CricbuzzJson,
+) -> Vec<Alert> {
+    let score = score_line(curr);
+    let alert = |kind: AlertKind, description: String, player: &str| Alert {
+        kind,
+        match_id,
+        match_name: match_name.to_string(),
+        description,
+        player: player.to_string(),
+        score: score.clone(),
+    };
+    let mut alerts = vec![];
+
+    if total_wickets(curr) > total_wickets(prev) {
+        let description = match curr.last_wicket() {
+            Some(wkt) => format!("Wicket! {}", wkt),
+            None => "Wicket!".to_string(),
+        };
+        let player = curr
+            .last_wicket()
+            .as_deref()
+            .map(dismissed_batsman)
+            .unwrap_or_default();
+        alerts.push(alert(AlertKind::Wicket, description, player));
+    }
+
+    for bat in [
+        &curr.miniscore.batsman_striker,
+        &curr.miniscore.batsman_non_striker,
+    ] {
+        let prev_bat = match previous_batsman(prev, bat) {
+            Some(prev_bat) => prev_bat,
+            None => continue,
+        };
+
+        // Several boundaries may have been hit since the last refresh
+        for (hit, prev_hit, kind, name) in [
+            (bat.bat_fours, prev_bat.bat_fours, AlertKind::Four, "four"),
+            (bat.bat_sixes, prev_bat.bat_sixes, AlertKind::Six, "six"),
+        ] {
+            let description = match hit.saturating_sub(prev_hit) {
+                0 => continue,
+                1 => format!("{} hit a {}", bat.bat_name, name),
+                n => format!("{} hit {} {}es", bat.bat_name, n, name),
+            };
+            let description = format!("{}, {} ({})", description, bat.bat_runs, bat.bat_balls);
+            alerts.push(alert(kind, description, &bat.bat_name));
+        }
+
+        for (milestone, kind) in [(50, AlertKind::Fifty), (100, AlertKind::Hundred)] {
+            if prev_bat.bat_runs < milestone && bat.bat_runs >= milestone {
+                let description = format!(
+                    "{} reached {} ({})",
+                    bat.bat_name, bat.bat_runs, bat.bat_balls
+                );
+                alerts.push(alert(kind, description, &bat.bat_name));
+            }
+        }
+    }
+
+    for bowl in [
+        &curr.miniscore.bowler_striker,
+        &curr.miniscore.bowler_non_striker,
+    ] {
+        let prev_wkts = match previous_bowler(prev, bowl) {
+            Some(prev_bowl) => prev_bowl.bowl_wkts,
+            None => continue,
+        };
+        if prev_wkts < 5 && bowl.bowl_wkts >= 5 {
+            let description = format!(
+                "{} took {}/{} ({})",
+                bowl.bowl_name, bowl.bowl_wkts, bowl.bowl_runs, bowl.bowl_ovs
+            );
+            alerts.push(alert(AlertKind::FiveFor, description, &bowl.bowl_name));
+        }
+    }
+
+    if !prev.is_complete() && curr.is_complete() {
+        alerts.push(alert(AlertKind::Result, curr.result_summary(), ""));
+    }
+
+    alerts
+}
+
+/// Runs a shell command for an alert in the background, without waiting for it to finish
+pub fn run_hook(command: &str, alert: &Alert) {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+
+    // The output of the command would be drawn over the UI
+    let child = cmd
+        .arg(command)
+        .env("CRICKET_EVENT", alert.kind.name())
+        .env("CRICKET_MATCH_ID", alert.match_id.to_string())
+        .env("CRICKET_MATCH", &alert.match_name)
+        .env("CRICKET_DESCRIPTION", &alert.description)
+        .env("CRICKET_PLAYER", &alert.player)
+        .env("CRICKET_SCORE", &alert.score)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    if let Ok(mut child) = child {
+        // Reap the command once it exits
+        tokio::spawn(async move {
+            let _ = child.wait().await;
+        });
+    }
+}
+
+/// Returns the name of the batsman from the description of a wicket.
+/// Eg. "Liton Das c Kusal Perera b Chameera 43(48)" is the wicket of Liton Das.
+fn dismissed_batsman(wkt: &str) -> &str {
+    let end = [" c ", " b ", " lbw ", " st ", " run out", " hit wicket"]
+        .iter()
+        .filter_map(|how| wkt.find(how))
+        .min()
+        .unwrap_or(wkt.len());
+    wkt[..end].trim()
+}
+
+/// Returns the wickets fallen in all the innings of a match
+fn total_wickets(json: &CricbuzzJson) -> u32 {
+    json.miniscore
+        .match_score_details
+        .innings_score_list
+        .iter()
+        .map(|inns| inns.wickets)
+        .sum()
+}
+
+/// Returns the score of the team batting. Eg. "BAN 257/6 (48)"
+fn score_line(json: &CricbuzzJson) -> String {
+    let msd = &json.miniscore.match_score_details;
+    match msd
+        .innings_score_list
+        .iter()
+        .find(|inns| inns.innings_id == json.miniscore.innings_id)
+    {
+        Some(inns) => format!(
+            "{} {}/{} ({})",
+            inns.bat_team_name, inns.score, inns.wickets, inns.overs
+        ),
+        None => String::new(),
+    }
+}
+
+/// Returns the details of a batsman in an earlier snapshot, if they were batting then
+fn previous_batsman<'a>(
+    prev: &'a CricbuzzJson,
+    bat: &CricbuzzMiniscoreBatsman,
+) -> Option<&'a CricbuzzMiniscoreBatsman> {
+    [
+        &prev.miniscore.batsman_striker,
+        &prev.miniscore.batsman_non_striker,
+    ]
+    .iter()
+    .copied()
+    .find(|b| b.bat_id != 0 && b.bat_id == bat.bat_id)
+}
+
+/// Returns the details of a bowler in an earlier snapshot, if they were bowling then
+fn previous_bowler<'a>(
+    prev: &'a CricbuzzJson,
+    bowl: &CricbuzzMiniscoreBowler,
+) -> Option<&'a CricbuzzMiniscoreBowler> {
+    [
+        &prev.miniscore.bowler_striker,
+        &prev.miniscore.bowler_non_striker,
+    ]
+    .iter()
+    .copied()
+    .find(|b| b.bowl_id != 0 && b.bowl_id == bowl.bowl_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alert::{detect_alerts, dismissed_batsman, AlertKind},
+        test_utils::load_json,
+    };
+
+    #[test]
+    fn test_detect_alerts() {
+        let prev = load_json("cricbuzz_odi_second_innings.json");
+        assert!(detect_alerts(36096, "BAN vs SL", &prev, &prev).is_empty());
+
+        let mut curr = load_json("cricbuzz_odi_second_innings.json");
+        curr.miniscore.match_score_details.innings_score_list[0].wickets += 1;
+        curr.miniscore.batsman_striker.bat_runs = 52;
+        curr.miniscore.bowler_striker.bowl_wkts = 5;
+
+        let alerts = detect_alerts(36096, "BAN vs SL", &prev, &curr);
+        let kinds: Vec<AlertKind> = alerts.iter().map(|a| a.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![AlertKind::Wicket, AlertKind::Fifty, AlertKind::FiveFor]
+        );
+        assert_eq!(alerts[1].player, curr.miniscore.batsman_striker.bat_name);
+        assert_eq!(alerts[1].score, "SL 41/2 (7.2)");
+
+        let complete = load_json("cricbuzz_odi_complete.json");
+        let alerts = detect_alerts(36096, "BAN vs SL", &prev, &complete);
+        let result = alerts.last().unwrap();
+        assert_eq!(result.kind, AlertKind::Result);
+        assert_eq!(result.description, "Bangladesh won by 33 runs");
+    }
+
+    #[test]
+    fn test_detect_boundary_alerts() {
+        let prev = load_json("cricbuzz_odi_second_innings.json");
+        let mut curr = load_json("cricbuzz_odi_second_innings.json");
+        curr.miniscore.batsman_striker.bat_runs += 4;
+        curr.miniscore.batsman_striker.bat_balls += 1;
+        curr.miniscore.batsman_striker.bat_fours += 1;
+        curr.miniscore.batsman_non_striker.bat_runs += 12;
+        curr.miniscore.batsman_non_striker.bat_balls += 2;
+        curr.miniscore.batsman_non_striker.bat_sixes += 2;
+
+        let alerts = detect_alerts(36096, "BAN vs SL", &prev, &curr);
+        let kinds: Vec<AlertKind> = alerts.iter().map(|a| a.kind).collect();
+        assert_eq!(kinds, vec![AlertKind::Four, AlertKind::Six]);
+        assert_eq!(alerts[0].description, "Pathum Nissanka hit a four, 12 (12)");
+        assert_eq!(alerts[0].player, "Pathum Nissanka");
+        assert_eq!(alerts[1].description, "Kusal Perera hit 2 sixes, 22 (16)");
+
+        // Boundaries of a new batsman are not compared with the previous one
+        curr.miniscore.batsman_striker.bat_id += 1;
+        let alerts = detect_alerts(36096, "BAN vs SL", &prev, &curr);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, AlertKind::Six);
+    }
+
+    #[test]
+    fn test_dismissed_batsman() {
+        assert_eq!(
+            dismissed_batsman("Liton Das c Kusal Perera b Chameera 43(48)"),
+            "Liton Das"
+        );
+        assert_eq!(
+            dismissed_batsman("Tamim Iqbal lbw b Udana 1(5)"),
+            "Tamim Iqbal"
+        );
+        assert_eq!(
+            dismissed_batsman("Mushfiqur Rahim run out (Shanaka) 84(87)"),
+            "Mushfiqur Rahim"
+        );
+    }
+}
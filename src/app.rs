@@ -3,48 +3,249 @@
 //! The `app` module is basically used to maintain the app state.
 //! In this case, all live matches information.
 
-use reqwest::Client;
-use scraper::{ElementRef, Html, Selector};
+use std::{
+    fmt,
+    future::Future,
+    num::ParseIntError,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use futures::future::join_all;
+use serde::Serialize;
+use tokio::{sync::Semaphore, time};
+
+use crate::{
+    alert::{detect_alerts, Alert},
+    config::MatchFilter,
+    cricbuzz_api::{CricbuzzJson, CricbuzzOverSeparator},
+    diff::{diff_match, MatchDiff},
+    provider::{CricbuzzProvider, ListedMatch, LiveMatch, ProviderError, ScoreProvider},
+};
+
+/// Maximum number of requests to the provider in flight at the same time
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+/// Time after which a request to the provider is given up on
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A statistic scraped from a scorecard. Holds the parsed value for computations and the
+/// text as it appeared on the scorecard for display.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Stat<T> {
+    pub value: T,
+    pub text: String,
+}
+
+impl<T: FromStr + Default> Stat<T> {
+    /// Parses the statistic from its scorecard text, defaulting the value if the text is
+    /// not a valid number
+    pub fn parse(text: &str) -> Stat<T> {
+        let text = text.trim();
+        Stat {
+            value: text.parse().unwrap_or_default(),
+            text: text.to_string(),
+        }
+    }
+}
+
+impl<T> fmt::Display for Stat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&self.text)
+    }
+}
+
+/// Overs bowled, stored as completed overs and the balls bowled in the current over.
+/// Eg. 4.2 overs is 4 overs and 2 balls, i.e. 26 balls in total.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Overs {
+    pub overs: u32,
+    pub balls: u32,
+}
+
+impl Overs {
+    /// Returns the overs corresponding to a number of legal deliveries
+    pub fn from_balls(balls: u32) -> Overs {
+        Overs {
+            overs: balls / 6,
+            balls: balls % 6,
+        }
+    }
+
+    /// Returns the overs given as a decimal number by Cricbuzz. Eg. 4.2 overs
+    pub fn from_decimal(overs: f32) -> Overs {
+        let completed = overs.trunc();
+        let balls = ((overs - completed) * 10.0).round() as u32;
+        Overs::from_balls(completed as u32 * 6 + balls)
+    }
+
+    /// Returns the total number of legal deliveries
+    pub fn total_balls(&self) -> u32 {
+        self.overs * 6 + self.balls
+    }
+}
+
+impl FromStr for Overs {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Overs, ParseIntError> {
+        let (overs, balls): (u32, u32) = match s.trim().split_once('.') {
+            Some((o, b)) => (o.parse()?, b.parse()?),
+            None => (s.trim().parse()?, 0),
+        };
 
-use crate::cricbuzz_api::CricbuzzJson;
+        Ok(Overs::from_balls(overs * 6 + balls))
+    }
+}
+
+impl fmt::Display for Overs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.balls == 0 {
+            write!(f, "{}", self.overs)
+        } else {
+            write!(f, "{}.{}", self.overs, self.balls)
+        }
+    }
+}
 
 /// This struct represents a bowler's statistics in a live match.
-#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct BowlerInfo {
     pub name: String,
-    pub overs: String,
-    pub maidens: String,
-    pub runs: String,
-    pub wickets: String,
-    pub no_balls: String,
-    pub wides: String,
-    pub economy: String,
+    pub overs: Stat<Overs>,
+    pub maidens: Stat<u32>,
+    pub runs: Stat<u32>,
+    pub wickets: Stat<u32>,
+    pub no_balls: Stat<u32>,
+    pub wides: Stat<u32>,
+    pub economy: Stat<f32>,
 }
 
-/// This struct represents a bowler's statistics in a live match.
-#[derive(Default, Debug, Clone, Eq, PartialEq)]
+/// This struct represents a batsman's statistics in a live match.
+#[derive(Default, Debug, Clone, PartialEq, Serialize)]
 pub struct BatsmanInfo {
     pub name: String,
     pub status: String,
-    pub runs: String,
-    pub balls: String,
-    pub fours: String,
-    pub sixes: String,
-    pub strike_rate: String,
+    pub runs: Stat<u32>,
+    pub balls: Stat<u32>,
+    pub fours: Stat<u32>,
+    pub sixes: Stat<u32>,
+    pub strike_rate: Stat<f32>,
 }
 
 /// This struct represents all the information related to the batsmen and bowlers in a
 /// particular innings of a match.
-#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct MatchInningsInfo {
     /// Holds all the batsmen details that is present in a scorecard
     pub batsman_details: Vec<BatsmanInfo>,
-    pub yet_to_bat: String,
+    /// Extra runs conceded in the innings
+    pub extras: Stat<u32>,
+    /// Breakup of the extras. Eg. b 0, lb 2, w 0, nb 0, p 0
+    pub extras_details: String,
+    /// Total runs scored in the innings
+    pub total: Stat<u32>,
+    /// Wickets and overs of the innings total. Eg. 1 wkts, 7.1 Ov
+    pub total_details: String,
+    /// Batsmen who are yet to bat, or did not bat once the innings is over
+    pub yet_to_bat: Vec<String>,
+    /// Score at which each wicket fell
+    pub fall_of_wickets: Vec<FallOfWicket>,
     /// Holds all the bowlers details that is present in a scorecard
     pub bowler_details: Vec<BowlerInfo>,
 }
 
+/// This struct represents the fall of a wicket in an innings. Eg. 30-1 (Gunathilaka, 4.6)
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct FallOfWicket {
+    pub score: Stat<u32>,
+    pub wicket: Stat<u32>,
+    pub batsman: String,
+    pub overs: Stat<Overs>,
+}
+
+impl fmt::Display for FallOfWicket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} ({}, {})",
+            self.score, self.wicket, self.batsman, self.overs
+        )
+    }
+}
+
+/// Runs scored in a completed over of an innings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OverSummary {
+    pub innings_id: u32,
+    /// Number of the over, starting from 1
+    pub over: u32,
+    /// Short name of the batting team
+    pub bat_team_name: String,
+    /// Runs scored in the over
+    pub runs: u32,
+    /// Wickets that fell in the over
+    pub wickets: u32,
+    /// Total runs of the innings at the end of the over
+    pub score: u32,
+    /// Total wickets of the innings at the end of the over
+    pub total_wickets: u32,
+    /// Whether the over was bowled during a powerplay
+    pub powerplay: bool,
+}
+
+impl From<&CricbuzzOverSeparator> for OverSummary {
+    fn from(sep: &CricbuzzOverSeparator) -> OverSummary {
+        OverSummary {
+            innings_id: sep.innings_id,
+            // The over ending with ball 6.6 is the 7th over
+            over: sep.over_num as u32 + 1,
+            bat_team_name: sep.bat_team_name.clone(),
+            runs: sep.runs,
+            wickets: sep
+                .o_summary
+                .split_whitespace()
+                .filter(|ball| is_wicket_ball(ball))
+                .count() as u32,
+            score: sep.score,
+            total_wickets: sep.wickets,
+            powerplay: false,
+        }
+    }
+}
+
+/// Returns whether a ball of an over summary, eg. "W", "1W" or "Wd+W", took a wicket.
+/// Wides are written as "Wd", so they are not counted.
+fn is_wicket_ball(ball: &str) -> bool {
+    ball.replace("Wd", "").contains('W')
+}
+
+/// Total an innings would end with if runs were scored at a run rate in the overs left
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectedScore {
+    /// What the run rate is. Eg. "CRR+1" or "8"
+    pub label: &'static str,
+    pub run_rate: f32,
+    pub score: u32,
+}
+
+/// What the team batting last needs to win the match
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Chase {
+    pub target: u32,
+    /// Runs still needed to win
+    pub runs_needed: u32,
+    /// Legal deliveries left, `None` when they are not known, as in Tests before their
+    /// last day
+    pub balls_remaining: Option<u32>,
+    pub wickets_in_hand: u32,
+    pub current_run_rate: f32,
+    /// Runs an over needed to win, `None` when the balls left are not known
+    pub required_run_rate: Option<f32>,
+}
+
 /// This holds all the information pertaining to a single live match
+#[derive(Serialize)]
 pub struct MatchInfo {
     /// Short form of the teams playing the match. Eg. IND vs NZ - Live
     pub match_short_name: String,
@@ -53,148 +254,468 @@ pub struct MatchInfo {
     /// Link from where live statistics of the match can be obtained
     pub cricbuzz_match_api_link: String,
     /// A struct representation of the JSON obtained from Cricbuzz containing all relevant information.
+    #[serde(skip)]
     pub cricbuzz_info: CricbuzzJson,
     /// All the innings scorecard statistics of a particular match
+    #[serde(skip)]
     pub scorecard: Vec<MatchInningsInfo>,
+    /// Every over seen since the match was first fetched, ordered by innings and over.
+    /// The response only contains the last few overs, so they are collected over time.
+    pub over_history: Vec<OverSummary>,
+    /// When the details of the match were last fetched successfully
+    pub last_refresh: SystemTime,
+    /// Whether the details or the scorecard of the match could not be fetched by the
+    /// latest refresh, so what is shown may be out of date
+    pub refresh_failed: bool,
+    /// Whether the match was opened from the match browser, in which case it is kept even
+    /// though it is not live
+    pub pinned: bool,
 }
 
-/// This contains all the live matches that are currently being played and also a connection client
-#[derive(Default)]
+/// The most recent error that occurred while fetching match details
+#[derive(Debug)]
+pub struct AppError {
+    /// Match whose details could not be fetched, if the error was specific to a match
+    pub match_id: Option<u32>,
+    pub error: ProviderError,
+    /// When the error occurred
+    pub time: SystemTime,
+}
+
+/// Details fetched by a refresh of the live matches, which are applied to the app with
+/// `App::apply_update`
+pub struct AppUpdate {
+    /// IDs of all the matches that are currently live
+    live_match_ids: Result<Vec<u32>, ProviderError>,
+    /// Details and scorecard of every match that was fetched
+    matches: Vec<(u32, FetchedMatch)>,
+    /// Details and scorecard of the matches which went live since the last refresh
+    new_matches: Vec<(LiveMatch, FetchedMatch)>,
+}
+
+/// Details of a match opened from the match browser, which are added to the app with
+/// `App::open_match`
+pub struct OpenedMatch {
+    short_name: String,
+    match_id: u32,
+    fetched: FetchedMatch,
+}
+
+/// What is known about a match listed in the match browser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowserStatus {
+    /// The match has not started yet. Holds its start time in milliseconds since the Unix
+    /// epoch
+    Upcoming(u64),
+    /// The match has finished. Holds its result. Eg. "Scotland won by 7 wkts"
+    Completed(String),
+}
+
+/// A match which is not live, listed in the match browser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserEntry {
+    /// Short form of the teams playing the match. Eg. IND vs NZ
+    pub short_name: String,
+    /// Number used by the provider to identify a match
+    pub match_id: u32,
+    /// The match and the series it is a part of. Eg. 1st ODI, Sri Lanka tour of Bangladesh
+    pub description: String,
+    pub status: BrowserStatus,
+}
+
+/// Results of the requests run in background tasks, sent back to the UI loop
+pub enum Fetched {
+    /// A refresh of the matches shown by the app
+    Update(AppUpdate),
+    /// The matches listed in the match browser
+    Browser(Result<Vec<BrowserEntry>, ProviderError>),
+    /// A match opened from the match browser
+    Opened(Box<OpenedMatch>),
+}
+
+/// Result of fetching the details and the scorecard of a match
+type FetchedMatch = (
+    Result<CricbuzzJson, ProviderError>,
+    Result<Vec<MatchInningsInfo>, ProviderError>,
+);
+
+/// This contains all the live matches that are currently being played and also the provider
+/// from which their details are obtained
 pub struct App {
-    /// Connection client to send multiple requests and obtain updates
-    req_clt: Client,
+    /// Source of all the live match information
+    provider: Arc<dyn ScoreProvider>,
+    /// Every request to the provider waits for one of these permits, so refreshing the
+    /// matches, listing the match browser and opening a match together stay within
+    /// `MAX_CONCURRENT_REQUESTS`
+    request_permits: Arc<Semaphore>,
     /// Details about all the live matches
     pub matches_info: Vec<MatchInfo>,
+    /// Whether the match details are being refreshed in the background
+    pub refreshing: bool,
+    /// The most recent error, kept around so it can be shown in the UI
+    pub last_error: Option<AppError>,
+    /// Upcoming and recently completed matches, `None` while they are being fetched
+    pub browser: Option<Vec<BrowserEntry>>,
+    /// Decides which of the live matches are shown
+    pub filter: MatchFilter,
+    /// Short names of the teams whose matches are shown first
+    pub favourite_teams: Vec<String>,
+    /// Live matches which are not shown, as they did not pass the filter or were closed
+    ignored_match_ids: Vec<u32>,
+    /// Whether matches are added when they go live, which is not the case when following
+    /// chosen matches
+    tracks_live_matches: bool,
+    /// What happened in the matches since the alerts were last taken
+    alerts: Vec<Alert>,
+    /// What changed in the matches since the diffs were last taken, `None` unless they are
+    /// tracked as only the server pushes them
+    diffs: Option<Vec<MatchDiff>>,
 }
 
-const CRICBUZZ_URL: &str = "https://www.cricbuzz.com";
-const CRICBUZZ_MATCH_API: &str = "https://www.cricbuzz.com/api/cricket-match/commentary/";
-const CRICBUZZ_MATCH_SCORECARD_API: &str = "https://www.cricbuzz.com/api/html/cricket-scorecard/";
+impl Default for App {
+    fn default() -> App {
+        App {
+            provider: Arc::new(CricbuzzProvider::default()),
+            request_permits: new_request_permits(),
+            matches_info: vec![],
+            refreshing: false,
+            last_error: None,
+            browser: None,
+            filter: MatchFilter::default(),
+            favourite_teams: vec![],
+            ignored_match_ids: vec![],
+            tracks_live_matches: true,
+            alerts: vec![],
+            diffs: None,
+        }
+    }
+}
 
 impl App {
-    /// Returs a new App containing all live matches
-    pub async fn new() -> App {
-        let mut matches_info = vec![];
-        let mut match_name_id: Vec<(String, String)> = vec![];
-        let mut scorecard: Vec<MatchInningsInfo> = vec![];
-        let req_clt = Client::new();
+    /// Returs a new App containing all live matches which pass the filter
+    pub async fn new(provider: Box<dyn ScoreProvider>, filter: MatchFilter) -> App {
+        let request_permits = new_request_permits();
+        let mut last_error = None;
 
         // First get all currently live matches
-        match get_all_live_matches_id_and_short_name(&req_clt).await {
-            Ok(v) => match_name_id = v,
+        let live_matches = match with_timeout(&request_permits, provider.live_matches()).await {
+            Ok(v) => v,
             Err(e) => {
-                println!("{:?}", e);
+                last_error = Some(AppError::new(None, e));
+                vec![]
             }
         };
 
         // For each live match populate required data
-        for (name, id) in &match_name_id {
-            if let Ok(match_id) = id.parse() {
-                if let Ok(json) = get_match_info_from_id(&req_clt, match_id).await {
-                    if prepare_scorecard(&req_clt, match_id, &mut scorecard)
-                        .await
-                        .is_ok()
-                    {
-                        matches_info.push(MatchInfo::new(
-                            name.to_string(),
-                            match_id,
-                            format!("{}{}", String::from(CRICBUZZ_MATCH_API), match_id),
-                            json,
-                            scorecard.clone(),
-                        ));
-                        scorecard.clear();
-                    } else {
-                        matches_info.push(MatchInfo::new(
-                            name.to_string(),
-                            match_id,
-                            format!("{}{}", String::from(CRICBUZZ_MATCH_API), match_id),
-                            json,
-                            vec![],
-                        ));
-                    }
+        let provider_ref = provider.as_ref();
+        let permits = request_permits.as_ref();
+        let fetched = join_all(live_matches.into_iter().map(|lm| async move {
+            let res = fetch_match(provider_ref, permits, lm.match_id).await;
+            (lm, res)
+        }))
+        .await;
+
+        let mut matches_info = vec![];
+        let mut ignored_match_ids = vec![];
+        for (lm, (json, scorecard)) in fetched {
+            match json {
+                Ok(json) if !filter.matches(&json) => ignored_match_ids.push(lm.match_id),
+                Ok(json) => {
+                    let scorecard =
+                        fetched_scorecard(&mut last_error, lm.match_id, &json, scorecard);
+                    matches_info.push(MatchInfo::new(
+                        lm.short_name,
+                        lm.match_id,
+                        provider.match_link(lm.match_id),
+                        json,
+                        scorecard.unwrap_or_default(),
+                    ))
                 }
+                Err(e) => last_error = Some(AppError::new(Some(lm.match_id), e)),
             }
         }
+        if let Some(e) = provider.take_failure() {
+            last_error = Some(AppError::new(None, e));
+        }
 
         App {
-            req_clt,
+            provider: Arc::from(provider),
+            request_permits,
             matches_info,
+            refreshing: false,
+            last_error,
+            browser: None,
+            filter,
+            favourite_teams: vec![],
+            ignored_match_ids,
+            tracks_live_matches: true,
+            alerts: vec![],
+            diffs: None,
         }
     }
 
-    pub async fn new_with_match_id(match_id: u32) -> App {
+    /// Returns a new App containing only the given matches, whether they are live or not.
+    /// No other matches are added when they go live.
+    pub async fn new_with_match_ids(provider: Box<dyn ScoreProvider>, match_ids: Vec<u32>) -> App {
+        let request_permits = new_request_permits();
         let mut matches_info = vec![];
-        let mut scorecard: Vec<MatchInningsInfo> = vec![];
-        let req_clt = Client::new();
-
-        if let Ok(json) = get_match_info_from_id(&req_clt, match_id).await {
-            let short_name = format!("{} vs {}", json.home_team_name(), json.away_team_name());
-            if prepare_scorecard(&req_clt, match_id, &mut scorecard)
-                .await
-                .is_ok()
-            {
-                matches_info.push(MatchInfo::new(
-                    short_name,
-                    match_id,
-                    format!("{}{}", String::from(CRICBUZZ_MATCH_API), match_id),
-                    json,
-                    scorecard.clone(),
-                ));
-                scorecard.clear();
-            } else {
-                matches_info.push(MatchInfo::new(
-                    short_name,
-                    match_id,
-                    format!("{}{}", String::from(CRICBUZZ_MATCH_API), match_id),
-                    json,
-                    vec![],
-                ));
+        let mut last_error = None;
+
+        let provider_ref = provider.as_ref();
+        let permits = request_permits.as_ref();
+        let fetched = join_all(match_ids.into_iter().map(|match_id| async move {
+            (match_id, fetch_match(provider_ref, permits, match_id).await)
+        }))
+        .await;
+
+        for (match_id, fetched) in fetched {
+            match fetched {
+                (Ok(json), scorecard) => {
+                    let short_name =
+                        format!("{} vs {}", json.home_team_name(), json.away_team_name());
+                    let scorecard = fetched_scorecard(&mut last_error, match_id, &json, scorecard);
+                    let mut mi = MatchInfo::new(
+                        short_name,
+                        match_id,
+                        provider.match_link(match_id),
+                        json,
+                        scorecard.unwrap_or_default(),
+                    );
+                    mi.pinned = true;
+                    matches_info.push(mi);
+                }
+                (Err(e), _) => last_error = Some(AppError::new(Some(match_id), e)),
             }
         }
 
         App {
-            req_clt,
+            provider: Arc::from(provider),
+            request_permits,
             matches_info,
+            refreshing: false,
+            last_error,
+            browser: None,
+            filter: MatchFilter::default(),
+            favourite_teams: vec![],
+            ignored_match_ids: vec![],
+            tracks_live_matches: false,
+            alerts: vec![],
+            diffs: None,
         }
     }
 
-    /// Updates the App data, basically updates information on all the live matches
-    /// Also returns the indexes of the matches that are no longer live
-    pub async fn update_on_tick(&mut self) -> Vec<usize> {
-        // WARN: Update algorithm might be slow for larger number of matches
-        // For now this should be fine and not cause any bottlenecks
-        let mut match_name_id: Vec<(String, String)> = vec![];
-        let mut scorecard: Vec<MatchInningsInfo> = vec![];
+    /// Returns the provider, so that match details can be fetched away from the app
+    pub fn provider(&self) -> Arc<dyn ScoreProvider> {
+        Arc::clone(&self.provider)
+    }
 
-        match get_all_live_matches_id_and_short_name(&self.req_clt).await {
-            Ok(v) => match_name_id = v,
-            Err(e) => {
-                println!("{:?}", e);
-            }
-        };
+    /// Returns the permits limiting the requests to the provider, which are passed along
+    /// with the provider when fetching away from the app
+    pub fn request_permits(&self) -> Arc<Semaphore> {
+        Arc::clone(&self.request_permits)
+    }
+
+    /// Shows the matches of the favourite teams first, keeping the order of the rest
+    pub fn set_favourite_teams(&mut self, teams: Vec<String>) {
+        self.favourite_teams = teams;
+        let favourites: Vec<bool> = (0..self.matches_info.len())
+            .map(|idx| self.is_favourite(idx))
+            .collect();
 
-        let mut non_live_matches_idx: Vec<usize> = vec![];
-        for (idx, mi) in &mut self.matches_info.iter_mut().enumerate() {
-            if match_name_id
+        let mut matches: Vec<(bool, MatchInfo)> = favourites
+            .into_iter()
+            .zip(self.matches_info.drain(..))
+            .collect();
+        matches.sort_by_key(|(favourite, _)| !favourite);
+        self.matches_info = matches.into_iter().map(|(_, mi)| mi).collect();
+    }
+
+    /// Returns whether one of the favourite teams is playing a match
+    pub fn is_favourite(&self, idx: usize) -> bool {
+        let header = &self.matches_info[idx].cricbuzz_info.match_header;
+        self.favourite_teams.iter().any(|t| {
+            t.eq_ignore_ascii_case(&header.team1.short_name)
+                || t.eq_ignore_ascii_case(&header.team2.short_name)
+        })
+    }
+
+    /// Returns the IDs of all the matches shown by the app
+    #[cfg(test)]
+    pub fn match_ids(&self) -> Vec<u32> {
+        self.matches_info
+            .iter()
+            .map(|mi| mi.cricbuzz_match_id)
+            .collect()
+    }
+
+    /// Returns the IDs of all the matches the app knows about, whether they are shown or not.
+    /// Returns `None` if the app does not add matches when they go live.
+    pub fn known_match_ids(&self) -> Option<Vec<u32>> {
+        if !self.tracks_live_matches {
+            return None;
+        }
+
+        Some(
+            self.matches_info
                 .iter()
-                .any(|e| e.1 == mi.cricbuzz_match_id.to_string())
+                .map(|mi| mi.cricbuzz_match_id)
+                .chain(self.ignored_match_ids.iter().copied())
+                .collect(),
+        )
+    }
+
+    /// Returns the IDs of the matches whose details can still change, i.e. all the matches
+    /// which have not finished
+    pub fn refresh_match_ids(&self) -> Vec<u32> {
+        self.matches_info
+            .iter()
+            .filter(|mi| !mi.cricbuzz_info.is_complete())
+            .map(|mi| mi.cricbuzz_match_id)
+            .collect()
+    }
+
+    /// Removes a match, which is how finished matches are done away with. A closed match
+    /// which is still live is not added back on the next refresh.
+    pub fn close_match(&mut self, idx: usize) {
+        if idx < self.matches_info.len() {
+            let mi = self.matches_info.remove(idx);
+            self.ignored_match_ids.push(mi.cricbuzz_match_id);
+        }
+    }
+
+    /// Closes the finished matches whose result was fetched more than `grace` ago, for when
+    /// nobody is around to close them. Matches opened from the match browser or followed
+    /// by their IDs are kept.
+    pub fn close_completed_matches(&mut self, grace: Duration) {
+        let now = SystemTime::now();
+        let expired: Vec<usize> = self
+            .matches_info
+            .iter()
+            .enumerate()
+            .filter(|(_, mi)| {
+                // Finished matches are not refreshed, so they were last fetched when they
+                // finished
+                !mi.pinned
+                    && mi.cricbuzz_info.is_complete()
+                    && now
+                        .duration_since(mi.last_refresh)
+                        .is_ok_and(|since| since > grace)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        for idx in expired.into_iter().rev() {
+            self.close_match(idx);
+        }
+    }
+
+    /// Updates the App data with freshly fetched details of all the live matches
+    /// Also returns the indexes of the matches that are no longer live, in descending order
+    /// so that they can be removed one after the other
+    ///
+    /// Matches which went live since the last refresh are added after the existing matches
+    /// if they pass the filter.
+    ///
+    /// Matches whose details could not be fetched keep their previous details, and are only
+    /// removed once they are known to not be live anymore. Finished matches are kept to show
+    /// their result until they are closed, as are matches opened from the match browser.
+    pub fn apply_update(&mut self, update: AppUpdate) -> Vec<usize> {
+        let AppUpdate {
+            live_match_ids,
+            matches,
+            new_matches,
+        } = update;
+
+        for (lm, fetched) in new_matches {
+            match fetched {
+                (Ok(json), _) if !self.filter.matches(&json) => {
+                    self.ignored_match_ids.push(lm.match_id)
+                }
+                (Ok(json), scorecard) => {
+                    let scorecard =
+                        fetched_scorecard(&mut self.last_error, lm.match_id, &json, scorecard);
+                    self.matches_info.push(MatchInfo::new(
+                        lm.short_name,
+                        lm.match_id,
+                        self.provider.match_link(lm.match_id),
+                        json,
+                        scorecard.unwrap_or_default(),
+                    ))
+                }
+                (Err(e), _) => self.last_error = Some(AppError::new(Some(lm.match_id), e)),
+            }
+        }
+
+        for (match_id, fetched) in matches {
+            let mi = match self
+                .matches_info
+                .iter_mut()
+                .find(|mi| mi.cricbuzz_match_id == match_id)
             {
-                let mid = mi.cricbuzz_match_id;
-                if let Ok(json) = get_match_info_from_id(&self.req_clt, mid).await {
-                    if prepare_scorecard(&self.req_clt, mid, &mut scorecard)
-                        .await
-                        .is_ok()
-                    {
-                        mi.cricbuzz_info = json;
-                        mi.scorecard = scorecard.clone();
-                        scorecard.clear();
+                Some(mi) => mi,
+                None => continue,
+            };
+
+            match fetched {
+                (Ok(json), scorecard) => {
+                    // The previous scorecard is kept if only the scorecard could not be fetched
+                    match fetched_scorecard(&mut self.last_error, match_id, &json, scorecard) {
+                        Some(scorecard) => {
+                            mi.scorecard = scorecard;
+                            mi.refresh_failed = false;
+                        }
+                        None => mi.refresh_failed = true,
+                    }
+                    self.alerts.extend(detect_alerts(
+                        match_id,
+                        &mi.match_short_name,
+                        &mi.cricbuzz_info,
+                        &json,
+                    ));
+                    if let Some(diffs) = &mut self.diffs {
+                        diffs.extend(diff_match(
+                            match_id,
+                            &mi.match_short_name,
+                            &mi.cricbuzz_info,
+                            &json,
+                        ));
                     }
+                    mi.cricbuzz_info = json;
+                    mi.last_refresh = SystemTime::now();
+                    mi.update_over_history();
+                }
+                (Err(e), _) => {
+                    mi.refresh_failed = true;
+                    self.last_error = Some(AppError::new(Some(match_id), e));
                 }
-            } else {
-                non_live_matches_idx.push(idx);
             }
         }
 
+        if let Some(e) = self.provider.take_failure() {
+            self.last_error = Some(AppError::new(None, e));
+        }
+
+        let live_match_ids = match live_match_ids {
+            Ok(ids) => ids,
+            Err(e) => {
+                self.last_error = Some(AppError::new(None, e));
+                return vec![];
+            }
+        };
+
+        let mut non_live_matches_idx: Vec<usize> = self
+            .matches_info
+            .iter()
+            .enumerate()
+            .filter(|(_, mi)| {
+                !mi.pinned
+                    && !mi.cricbuzz_info.is_complete()
+                    && !live_match_ids.contains(&mi.cricbuzz_match_id)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        non_live_matches_idx.reverse();
         for i in &non_live_matches_idx {
             self.matches_info.remove(*i);
         }
@@ -202,17 +723,96 @@ impl App {
         non_live_matches_idx
     }
 
+    /// Returns what happened in the matches since this was last called
+    pub fn take_alerts(&mut self) -> Vec<Alert> {
+        std::mem::take(&mut self.alerts)
+    }
+
+    /// Starts keeping what changed in the matches on every update
+    pub fn track_diffs(&mut self) {
+        self.diffs.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns what changed in the matches since this was last called, nothing if the
+    /// diffs are not tracked
+    pub fn take_diffs(&mut self) -> Vec<MatchDiff> {
+        self.diffs.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Stores the matches listed in the match browser
+    pub fn set_browser(&mut self, entries: Result<Vec<BrowserEntry>, ProviderError>) {
+        match entries {
+            Ok(entries) => self.browser = Some(entries),
+            Err(e) => {
+                self.last_error = Some(AppError::new(None, e));
+                self.browser = Some(vec![]);
+            }
+        }
+    }
+
+    /// Adds a match opened from the match browser to the app and returns its index.
+    /// Returns `None` if its details could not be fetched.
+    pub fn open_match(&mut self, opened: OpenedMatch) -> Option<usize> {
+        let OpenedMatch {
+            short_name,
+            match_id,
+            fetched,
+        } = opened;
+
+        // The match may have gone live or been opened already in the meantime
+        if let Some(idx) = self
+            .matches_info
+            .iter()
+            .position(|mi| mi.cricbuzz_match_id == match_id)
+        {
+            self.matches_info[idx].pinned = true;
+            return Some(idx);
+        }
+
+        match fetched {
+            (Ok(json), scorecard) => {
+                let scorecard = fetched_scorecard(&mut self.last_error, match_id, &json, scorecard);
+                let mut mi = MatchInfo::new(
+                    short_name,
+                    match_id,
+                    self.provider.match_link(match_id),
+                    json,
+                    scorecard.unwrap_or_default(),
+                );
+                mi.pinned = true;
+                self.matches_info.push(mi);
+                Some(self.matches_info.len() - 1)
+            }
+            (Err(e), _) => {
+                self.last_error = Some(AppError::new(Some(match_id), e));
+                None
+            }
+        }
+    }
+
+    /// Returns the short name of a match shown by the app
+    pub fn match_short_name(&self, match_id: u32) -> Option<&str> {
+        self.matches_info
+            .iter()
+            .find(|mi| mi.cricbuzz_match_id == match_id)
+            .map(|mi| mi.match_short_name.as_str())
+    }
+
     /// Returns a vector of short names of all the live matches
     pub fn get_all_matches_short_names(&self) -> Vec<String> {
         let names: Vec<String> = self
             .matches_info
             .iter()
             .map(|m| {
-                format!(
-                    "{} - {}",
-                    m.match_short_name,
-                    m.cricbuzz_info.match_format()
-                )
+                if m.cricbuzz_info.is_complete() {
+                    format!("{} - Result", m.match_short_name)
+                } else {
+                    format!(
+                        "{} - {}",
+                        m.match_short_name,
+                        m.cricbuzz_info.match_format()
+                    )
+                }
             })
             .collect();
         names
@@ -229,235 +829,387 @@ impl App {
     }
 }
 
-impl MatchInfo {
-    /// Returns a new struct containing live match information
-    fn new(
-        match_short_name: String,
-        cricbuzz_match_id: u32,
-        cricbuzz_match_api_link: String,
-        cricbuzz_info: CricbuzzJson,
-        scorecard: Vec<MatchInningsInfo>,
-    ) -> MatchInfo {
-        MatchInfo {
-            match_short_name,
-            cricbuzz_match_id,
-            cricbuzz_match_api_link,
-            cricbuzz_info,
-            scorecard,
-        }
+/// Fetches the latest details of the given matches, along with the live matches which are
+/// not known yet unless `known_ids` is `None`. Matches which are no longer live are fetched
+/// one last time, to find out whether they have finished.
+///
+/// This does not need the app, so it can be run in a background task while the UI keeps
+/// drawing the previous details.
+pub async fn fetch_update(
+    provider: &dyn ScoreProvider,
+    permits: &Semaphore,
+    match_ids: Vec<u32>,
+    known_ids: Option<Vec<u32>>,
+) -> AppUpdate {
+    let live_matches = with_timeout(permits, provider.live_matches()).await;
+    let new_live_matches: Vec<LiveMatch> = match (&live_matches, &known_ids) {
+        (Ok(live), Some(known_ids)) => live
+            .iter()
+            .filter(|lm| !known_ids.contains(&lm.match_id))
+            .cloned()
+            .collect(),
+        _ => vec![],
+    };
+    let live_match_ids = live_matches.map(|v| v.iter().map(|lm| lm.match_id).collect());
+
+    // Fetch all the matches at the same time, the requests are limited by the permits
+    let matches =
+        join_all(match_ids.into_iter().map(|match_id| async move {
+            (match_id, fetch_match(provider, permits, match_id).await)
+        }));
+    let new_matches = join_all(new_live_matches.into_iter().map(|lm| async move {
+        let fetched = fetch_match(provider, permits, lm.match_id).await;
+        (lm, fetched)
+    }));
+    let (matches, new_matches) = tokio::join!(matches, new_matches);
+
+    AppUpdate {
+        live_match_ids,
+        matches,
+        new_matches,
     }
 }
 
-/// Helper function to obtain all currently live matches from Cricbuzz's homepage
-// TODO: Need to improve method of getting all matches
-async fn get_all_live_matches_id_and_short_name(
-    req_clt: &Client,
-) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
-    let resp_html = req_clt.get(CRICBUZZ_URL).send().await?.text().await?;
-    let mut match_id_name = vec![];
+/// Fetches the upcoming and recently completed matches listed by the provider, upcoming
+/// matches first in the order they start
+///
+/// Matches whose details could not be fetched or which do not pass the filter are left out
+/// of the list.
+pub async fn fetch_browser(
+    provider: &dyn ScoreProvider,
+    permits: &Semaphore,
+    filter: &MatchFilter,
+) -> Result<Vec<BrowserEntry>, ProviderError> {
+    let listed = with_timeout(permits, provider.listed_matches()).await?;
+
+    let not_live = listed.into_iter().filter(|lm| lm.status != "Live");
+    let fetched = join_all(not_live.map(|lm| async move {
+        let json = with_timeout(permits, provider.match_info(lm.match_id)).await;
+        (lm, json)
+    }))
+    .await;
+
+    let mut entries: Vec<BrowserEntry> = fetched
+        .into_iter()
+        .filter_map(|(lm, json)| {
+            let json = json.ok().filter(|json| filter.matches(json))?;
+            BrowserEntry::new(lm, &json)
+        })
+        .collect();
+
+    // Sorting is stable, so completed matches stay in the order they were listed
+    entries.sort_by_key(|e| match e.status {
+        BrowserStatus::Upcoming(start) => (0, start),
+        BrowserStatus::Completed(_) => (1, 0),
+    });
+
+    Ok(entries)
+}
 
-    parse_all_live_matches_id_and_short_name(&resp_html, &mut match_id_name);
+/// Fetches a match selected in the match browser
+pub async fn fetch_opened_match(
+    provider: &dyn ScoreProvider,
+    permits: &Semaphore,
+    entry: &BrowserEntry,
+) -> OpenedMatch {
+    OpenedMatch {
+        short_name: entry.short_name.clone(),
+        match_id: entry.match_id,
+        fetched: fetch_match(provider, permits, entry.match_id).await,
+    }
+}
 
-    Ok(match_id_name)
+/// Runs a request to the provider once a permit is available, giving up on it after
+/// `FETCH_TIMEOUT`
+async fn with_timeout<T>(
+    permits: &Semaphore,
+    fut: impl Future<Output = Result<T, ProviderError>>,
+) -> Result<T, ProviderError> {
+    // The semaphore is never closed, and the permit is held until the request is done
+    let _permit = permits.acquire().await;
+    match time::timeout(FETCH_TIMEOUT, fut).await {
+        Ok(res) => res,
+        Err(_) => Err(ProviderError::Timeout(FETCH_TIMEOUT)),
+    }
 }
 
-/// Helper function which scrapes match IDs and short names from the Cricbuzz homepage
-///
-/// # Arguments
-/// * `html` - The HTML page
-/// * `match_id_name` - A vector containing a tuple of match short name and ID
-fn parse_all_live_matches_id_and_short_name(html: &str, match_id_name: &mut Vec<(String, String)>) {
-    let doc = Html::parse_document(html);
-
-    /* These unwraps will not panic */
-    let nav_sel = Selector::parse("nav.cb-mat-mnu").unwrap();
-    let sel_a = Selector::parse("a").unwrap();
-
-    if let Some(nav) = doc.select(&nav_sel).next() {
-        for link in nav.select(&sel_a) {
-            let text = link
-                .text()
-                .collect::<Vec<&str>>()
-                .concat()
-                .trim()
-                .to_string();
-            if !text.is_empty() && !text.eq("MATCHES") {
-                let words = text.split('-').collect::<Vec<&str>>();
-                if words.len() >= 2 {
-                    let status = words[1];
-                    if status.trim().eq("Live") {
-                        if let Some(href) = link.value().attr("href") {
-                            let split_str = href.split('/').collect::<Vec<&str>>();
-                            match_id_name
-                                .push((words[0].trim().to_string(), split_str[2].to_string()));
-                        }
-                    }
-                }
-            }
+/// Returns the scorecard of a match if it was fetched, otherwise keeps why it was not in
+/// `last_error`. Matches which have not started have no innings on their scorecard yet,
+/// which is not an error.
+fn fetched_scorecard(
+    last_error: &mut Option<AppError>,
+    match_id: u32,
+    json: &CricbuzzJson,
+    scorecard: Result<Vec<MatchInningsInfo>, ProviderError>,
+) -> Option<Vec<MatchInningsInfo>> {
+    let not_started = json
+        .miniscore
+        .match_score_details
+        .innings_score_list
+        .is_empty();
+
+    match scorecard {
+        Ok(scorecard) => Some(scorecard),
+        Err(ProviderError::Layout(_)) if not_started => Some(vec![]),
+        Err(e) => {
+            *last_error = Some(AppError::new(Some(match_id), e));
+            None
         }
     }
 }
 
-/// Obtains match information from the Cricbuzz API using the match ID
-async fn get_match_info_from_id(
-    req_clt: &Client,
+/// Fetches the details and the scorecard of a match at the same time
+async fn fetch_match(
+    provider: &dyn ScoreProvider,
+    permits: &Semaphore,
     match_id: u32,
-) -> Result<CricbuzzJson, Box<dyn std::error::Error>> {
-    let resp = req_clt
-        .get(format!("{}{}", String::from(CRICBUZZ_MATCH_API), match_id))
-        .send()
-        .await?
-        .text()
-        .await?;
-
-    if let Ok(res) = serde_json::from_str::<CricbuzzJson>(&resp) {
-        Ok(res)
-    } else {
-        Err("Failed to parse JSON".into())
-    }
+) -> FetchedMatch {
+    tokio::join!(
+        with_timeout(permits, provider.match_info(match_id)),
+        with_timeout(permits, provider.scorecard(match_id))
+    )
 }
 
-/// Helper function to parse and structure scorecard data from the HTML page
-async fn prepare_scorecard(
-    req_clt: &Client,
-    id: u32,
-    scorecard: &mut Vec<MatchInningsInfo>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let resp_html = req_clt
-        .get(format!(
-            "{}{}",
-            String::from(CRICBUZZ_MATCH_SCORECARD_API),
-            id
-        ))
-        .send()
-        .await?
-        .text()
-        .await?;
+/// Returns the permits shared by all the requests of an app to its provider
+fn new_request_permits() -> Arc<Semaphore> {
+    Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS))
+}
 
-    parse_scorecard(&resp_html, scorecard);
+impl BrowserEntry {
+    /// Returns the browser entry of a listed match, or `None` if the match is neither
+    /// upcoming nor completed
+    fn new(lm: ListedMatch, json: &CricbuzzJson) -> Option<BrowserEntry> {
+        let header = &json.match_header;
+        let status = if header.complete {
+            BrowserStatus::Completed(json.result_summary())
+        } else if matches!(header.state.as_str(), "Preview" | "Upcoming") {
+            BrowserStatus::Upcoming(header.match_start_timestamp)
+        } else {
+            return None;
+        };
 
-    Ok(())
+        let description = [header.match_description.as_str(), &header.series_name]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .copied()
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        Some(BrowserEntry {
+            short_name: lm.short_name,
+            match_id: lm.match_id,
+            description,
+            status,
+        })
+    }
 }
 
-/// Parse scorecard data and structure it
-fn parse_scorecard(html: &str, scorecard: &mut Vec<MatchInningsInfo>) {
-    let doc = Html::parse_document(html);
-
-    for ino in 1..5 {
-        // This unwrap will not panic
-        let inngs_sel = Selector::parse(format!("div[id=\"innings_{}\"]", ino).as_str()).unwrap();
-        if let Some(div) = doc.select(&inngs_sel).next() {
-            populate_innings_info(&div, scorecard);
+impl AppError {
+    /// Returns a new error which occurred just now
+    fn new(match_id: Option<u32>, error: ProviderError) -> AppError {
+        AppError {
+            match_id,
+            error,
+            time: SystemTime::now(),
         }
     }
 }
 
-/// Helper function to build structured innings information of a match
-///
-/// # Arguments
-///
-/// * `div` - Refers to the div element in the HTML page containing the innings information
-/// * `scorecard` - Vector of all innings information
-fn populate_innings_info(div: &ElementRef, scorecard: &mut Vec<MatchInningsInfo>) {
-    // These unwraps will never panic
-    let sel_scrd_items = Selector::parse("div.cb-scrd-itms").unwrap();
-    let sel_div = Selector::parse("div").unwrap();
-
-    let mut match_inngs_info = MatchInningsInfo::default();
-
-    for inner_div in div.select(&sel_scrd_items) {
-        // Check for batsman or bowler scorcard info
-        let num_child_div = inner_div.select(&sel_div).count();
-        if num_child_div == 7 {
-            // This is for batsman info
-            let mut bat_info = BatsmanInfo::default();
-            let mut divs = inner_div.select(&sel_div);
-            if let Some(bat_name_link) = divs.next() {
-                bat_info.name = bat_name_link
-                    .text()
-                    .collect::<Vec<&str>>()
-                    .concat()
-                    .trim()
-                    .to_string();
-            }
-
-            if let Some(div) = divs.next() {
-                bat_info.status = div
-                    .text()
-                    .collect::<Vec<&str>>()
-                    .concat()
-                    .trim()
-                    .to_string()
-            }
-
-            if let Some(div) = divs.next() {
-                bat_info.runs = div.inner_html().trim().to_string();
-            }
+impl MatchInfo {
+    /// Returns a new struct containing live match information
+    fn new(
+        match_short_name: String,
+        cricbuzz_match_id: u32,
+        cricbuzz_match_api_link: String,
+        cricbuzz_info: CricbuzzJson,
+        scorecard: Vec<MatchInningsInfo>,
+    ) -> MatchInfo {
+        let mut mi = MatchInfo {
+            match_short_name,
+            cricbuzz_match_id,
+            cricbuzz_match_api_link,
+            cricbuzz_info,
+            scorecard,
+            over_history: vec![],
+            last_refresh: SystemTime::now(),
+            refresh_failed: false,
+            pinned: false,
+        };
+        mi.update_over_history();
+        mi
+    }
 
-            if let Some(div) = divs.next() {
-                bat_info.balls = div.inner_html().trim().to_string();
-            }
-            if let Some(div) = divs.next() {
-                bat_info.fours = div.inner_html().trim().to_string();
-            }
-            if let Some(div) = divs.next() {
-                bat_info.sixes = div.inner_html().trim().to_string();
-            }
-            if let Some(div) = divs.next() {
-                bat_info.strike_rate = div.inner_html().trim().to_string();
+    /// Adds the overs present in the latest match details to the over history
+    pub fn update_over_history(&mut self) {
+        for sep in self.cricbuzz_info.over_summaries() {
+            let mut summary = OverSummary::from(sep);
+            summary.powerplay = self
+                .cricbuzz_info
+                .is_powerplay_over(summary.innings_id, summary.over);
+
+            let key = (summary.innings_id, summary.over);
+            match self
+                .over_history
+                .binary_search_by_key(&key, |o| (o.innings_id, o.over))
+            {
+                Ok(idx) => {
+                    // Powerplays of earlier innings are no longer present in the response
+                    summary.powerplay |= self.over_history[idx].powerplay;
+                    self.over_history[idx] = summary;
+                }
+                Err(idx) => self.over_history.insert(idx, summary),
             }
+        }
 
-            match_inngs_info.batsman_details.push(bat_info);
-        } else if num_child_div == 8 {
-            // This is for bowler info
-            let mut bowl_info = BowlerInfo::default();
-            let mut divs = inner_div.select(&sel_div);
-            if let Some(bowl_name_link) = divs.next() {
-                bowl_info.name = bowl_name_link
-                    .text()
-                    .collect::<Vec<&str>>()
-                    .concat()
-                    .trim()
-                    .to_string();
+        // The total wickets at the end of the previous over are more reliable than the
+        // balls of the over, which may not show run outs off extras
+        for idx in 0..self.over_history.len() {
+            let prev_wickets = match idx.checked_sub(1).map(|i| &self.over_history[i]) {
+                _ if self.over_history[idx].over == 1 => Some(0),
+                Some(prev)
+                    if prev.innings_id == self.over_history[idx].innings_id
+                        && prev.over + 1 == self.over_history[idx].over =>
+                {
+                    Some(prev.total_wickets)
+                }
+                _ => None,
+            };
+            if let Some(prev_wickets) = prev_wickets {
+                let over = &mut self.over_history[idx];
+                over.wickets = over.total_wickets.saturating_sub(prev_wickets);
             }
+        }
+    }
 
-            if let Some(div) = divs.next() {
-                bowl_info.overs = div.inner_html().trim().to_string();
+    /// Returns the legal deliveries left in the current innings of a limited overs match.
+    /// The overs left sent by Cricbuzz take precedence, as they account for innings
+    /// shortened by rain.
+    pub fn balls_remaining(&self) -> Option<u32> {
+        let json = &self.cricbuzz_info;
+        let total_overs = json.format_total_overs()?;
+
+        match json.overs_rem() {
+            Some(overs_rem) => Some(Overs::from_decimal(overs_rem).total_balls()),
+            None => {
+                let bowled = Overs::from_decimal(json.miniscore.overs).total_balls();
+                Some((total_overs * 6).saturating_sub(bowled))
             }
+        }
+    }
 
-            if let Some(div) = divs.next() {
-                bowl_info.maidens = div.inner_html().trim().to_string();
-            }
+    /// Returns the totals the first innings of an ODI or T20 would end with at the current
+    /// run rate, one and two runs an over more, and 6, 8 and 10 runs an over, like TV
+    /// broadcasts show. Empty for other innings and formats, and once the innings is over.
+    pub fn projected_scores(&self) -> Vec<ProjectedScore> {
+        let json = &self.cricbuzz_info;
+        if json.miniscore.innings_id != 1 || json.is_complete() {
+            return vec![];
+        }
 
-            if let Some(div) = divs.next() {
-                bowl_info.runs = div.inner_html().trim().to_string();
-            }
+        let inns = match json
+            .miniscore
+            .match_score_details
+            .innings_score_list
+            .iter()
+            .find(|inns| inns.innings_id == 1)
+        {
+            Some(inns) => inns,
+            None => return vec![],
+        };
+        let bowled = Overs::from_decimal(inns.overs).total_balls();
+        let balls_remaining = match self.balls_remaining() {
+            Some(balls) if balls > 0 && bowled > 0 && inns.wickets < 10 => balls,
+            _ => return vec![],
+        };
 
-            if let Some(div) = divs.next() {
-                bowl_info.wickets = div.inner_html().trim().to_string();
-            }
+        let crr = if json.miniscore.current_run_rate > 0.0 {
+            json.miniscore.current_run_rate
+        } else {
+            inns.score as f32 * 6.0 / bowled as f32
+        };
 
-            if let Some(div) = divs.next() {
-                bowl_info.no_balls = div.inner_html().trim().to_string();
-            }
+        [
+            ("CRR", crr),
+            ("CRR+1", crr + 1.0),
+            ("CRR+2", crr + 2.0),
+            ("6", 6.0),
+            ("8", 8.0),
+            ("10", 10.0),
+        ]
+        .iter()
+        .map(|&(label, run_rate)| ProjectedScore {
+            label,
+            run_rate,
+            score: inns.score + (run_rate * balls_remaining as f32 / 6.0).round() as u32,
+        })
+        .collect()
+    }
 
-            if let Some(div) = divs.next() {
-                bowl_info.wides = div.inner_html().trim().to_string();
-            }
+    /// Returns what the team batting second in an ODI or T20, or batting fourth in a Test,
+    /// needs to win. `None` for other innings and once the match is over.
+    pub fn chase(&self) -> Option<Chase> {
+        let json = &self.cricbuzz_info;
+        let last_innings = if json.format_total_overs().is_some() {
+            2
+        } else if json.match_format() == "TEST" {
+            4
+        } else {
+            return None;
+        };
+        if json.miniscore.innings_id != last_innings || json.is_complete() {
+            return None;
+        }
 
-            if let Some(div) = divs.next() {
-                bowl_info.economy = div.inner_html().trim().to_string();
+        let target = json.miniscore.target;
+        let inns = json
+            .miniscore
+            .match_score_details
+            .innings_score_list
+            .iter()
+            .find(|inns| inns.innings_id == last_innings)?;
+        let runs_needed = target.checked_sub(inns.score).filter(|&runs| runs > 0)?;
+
+        // On the last day of a Test, the overs left in the day are the overs left in the match.
+        // Tests are scheduled for four or five days, and some have been extended past that.
+        let balls_remaining = if json.match_format() == "TEST" {
+            match (
+                json.match_header.day_number,
+                json.scheduled_days(),
+                json.overs_rem(),
+            ) {
+                (Some(day), Some(last_day), Some(overs_rem)) if day >= last_day => {
+                    Some(Overs::from_decimal(overs_rem).total_balls())
+                }
+                _ => None,
             }
+        } else {
+            self.balls_remaining()
+        };
+        let required_run_rate = match balls_remaining {
+            Some(balls) if balls > 0 => Some(if json.miniscore.required_run_rate > 0.0 {
+                json.miniscore.required_run_rate
+            } else {
+                runs_needed as f32 * 6.0 / balls as f32
+            }),
+            _ => None,
+        };
 
-            match_inngs_info.bowler_details.push(bowl_info);
-        }
+        Some(Chase {
+            target,
+            runs_needed,
+            balls_remaining,
+            wickets_in_hand: 10u32.saturating_sub(inns.wickets),
+            current_run_rate: json.miniscore.current_run_rate,
+            required_run_rate,
+        })
     }
-    scorecard.push(match_inngs_info);
-}
 
-#[cfg(test)]
-pub fn parse_scorecard_from_file(file: &str, scorecard: &mut Vec<MatchInningsInfo>) {
-    parse_scorecard(file, scorecard);
+    /// Returns the over history of every innings in the order they were played
+    pub fn innings_over_history(&self) -> Vec<&[OverSummary]> {
+        self.over_history
+            .chunk_by(|a, b| a.innings_id == b.innings_id)
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -479,132 +1231,635 @@ pub fn create_match_info(
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use tokio::sync::Semaphore;
+
+    use crate::{
+        alert::AlertKind,
+        app::{
+            create_match_info, fetch_match, fetch_opened_match, fetch_update, is_wicket_ball, App,
+            AppUpdate, BrowserEntry, BrowserStatus, Chase, MatchInfo, MatchInningsInfo, Overs,
+            Stat, FETCH_TIMEOUT, MAX_CONCURRENT_REQUESTS,
+        },
+        config::MatchFilter,
+        cricbuzz_api::{CricbuzzJson, CricbuzzOverSeparator},
+        provider::{ListedMatch, LiveMatch, ProviderError, ScoreProvider},
+        test_utils::{load_json, load_match, load_scorecard},
+    };
+
+    /// Serves the ODI test data for a single live match
+    struct FileProvider;
+
+    #[async_trait]
+    impl ScoreProvider for FileProvider {
+        async fn live_matches(&self) -> Result<Vec<LiveMatch>, ProviderError> {
+            Ok(vec![LiveMatch {
+                short_name: "BAN vs SL".to_string(),
+                match_id: 36096,
+            }])
+        }
 
-    use insta;
+        async fn match_info(&self, _match_id: u32) -> Result<CricbuzzJson, ProviderError> {
+            Ok(load_json("cricbuzz_odi_first_innings.json"))
+        }
 
-    use crate::app::{parse_all_live_matches_id_and_short_name, parse_scorecard};
+        async fn scorecard(&self, _match_id: u32) -> Result<Vec<MatchInningsInfo>, ProviderError> {
+            Ok(load_scorecard("cricbuzz_odi_scorecard_first_innings.txt"))
+        }
 
-    // Path is relative to where `cargo test` command is run
-    const TEST_FILES_PATH: &str = "./tests/data/";
+        fn match_link(&self, match_id: u32) -> String {
+            format!("file://{}", match_id)
+        }
+    }
 
-    // Path is relative to where the testfile is present
-    const SNAPSHOTS_PATH: &str = "../tests/snapshots";
+    /// Serves the same data as `FileProvider` after a delay, keeping count of the requests
+    /// in flight
+    struct SlowProvider {
+        delay: Duration,
+        live_matches: u32,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
 
-    #[test]
-    fn test_parse_all_live_matches_id_and_short_name_four_live_matches() {
-        let fp = format!("{}{}", TEST_FILES_PATH, "cricbuzz_home_four_live.txt");
-        // This unwrap ideally will not panic
-        let html = fs::read_to_string(fp).unwrap();
+    impl SlowProvider {
+        fn new(delay: Duration, live_matches: u32) -> SlowProvider {
+            SlowProvider {
+                delay,
+                live_matches,
+                in_flight: AtomicUsize::new(0),
+                max_in_flight: AtomicUsize::new(0),
+            }
+        }
+
+        async fn request<T>(&self, res: Result<T, ProviderError>) -> Result<T, ProviderError> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            res
+        }
+    }
 
-        let res_match_id_name: Vec<(String, String)> = vec![
-            ("KENT vs GLAM".to_string(), "33238".to_string()),
-            ("HAM vs LEIC".to_string(), "33243".to_string()),
-            ("SUR vs MDX".to_string(), "33253".to_string()),
-            ("GLOUCS vs SOM".to_string(), "33248".to_string()),
-        ];
-        let mut match_id_name = vec![];
+    #[async_trait]
+    impl ScoreProvider for SlowProvider {
+        async fn live_matches(&self) -> Result<Vec<LiveMatch>, ProviderError> {
+            let live = (0..self.live_matches)
+                .map(|match_id| LiveMatch {
+                    short_name: "BAN vs SL".to_string(),
+                    match_id,
+                })
+                .collect();
+            self.request(Ok(live)).await
+        }
 
-        parse_all_live_matches_id_and_short_name(&html, &mut match_id_name);
+        async fn match_info(&self, match_id: u32) -> Result<CricbuzzJson, ProviderError> {
+            self.request(FileProvider.match_info(match_id).await).await
+        }
+
+        async fn scorecard(&self, match_id: u32) -> Result<Vec<MatchInningsInfo>, ProviderError> {
+            self.request(FileProvider.scorecard(match_id).await).await
+        }
 
-        assert_eq!(res_match_id_name, match_id_name);
+        fn match_link(&self, match_id: u32) -> String {
+            FileProvider.match_link(match_id)
+        }
     }
 
     #[test]
-    fn test_parse_all_live_matches_id_and_short_name_no_live_matches() {
-        let fp = format!("{}{}", TEST_FILES_PATH, "cricbuzz_home_no_live.txt");
-        // This unwrap ideally will not panic
-        let html = fs::read_to_string(fp).unwrap();
-
-        let res_match_id_name: Vec<(String, String)> = vec![];
-        let mut match_id_name = vec![];
+    fn test_parse_stats() {
+        let overs: Stat<Overs> = Stat::parse(" 4.2 ");
+        assert_eq!(overs.value.total_balls(), 26);
+        assert_eq!(overs.text, "4.2");
+        assert_eq!(format!("{:<5}|", overs), "4.2  |");
+
+        assert_eq!("10".parse::<Overs>(), Ok(Overs::from_balls(60)));
+        assert_eq!(Overs::from_balls(26).to_string(), "4.2");
+        assert!("x.1".parse::<Overs>().is_err());
+        assert_eq!(Overs::from_decimal(7.2), Overs::from_balls(44));
+        // Cricbuzz shows a completed innings of 50 overs as 49.6
+        assert_eq!(Overs::from_decimal(49.6).total_balls(), 300);
+
+        let econ: Stat<f32> = Stat::parse("15.60");
+        assert_eq!(econ.value, 15.6);
+        let runs: Stat<u32> = Stat::parse("-");
+        assert_eq!(runs.value, 0);
+        assert_eq!(runs.text, "-");
+    }
 
-        parse_all_live_matches_id_and_short_name(&html, &mut match_id_name);
+    #[test]
+    fn test_projected_scores() {
+        let match_info = |name: &str| load_match(name, None);
+
+        // 18/1 in 4.5 overs at 3.72 an over
+        let first_innings = match_info("cricbuzz_odi_first_innings.json");
+        assert_eq!(first_innings.balls_remaining(), Some(271));
+        let projected: Vec<(&str, u32)> = first_innings
+            .projected_scores()
+            .iter()
+            .map(|p| (p.label, p.score))
+            .collect();
+        assert_eq!(
+            projected,
+            vec![
+                ("CRR", 186),
+                ("CRR+1", 231),
+                ("CRR+2", 276),
+                ("6", 289),
+                ("8", 379),
+                ("10", 470)
+            ]
+        );
 
-        assert_eq!(res_match_id_name, match_id_name);
+        assert!(match_info("cricbuzz_odi_second_innings.json")
+            .projected_scores()
+            .is_empty());
+        let test_match = match_info("cricbuzz_test_first_innings.json");
+        assert_eq!(test_match.balls_remaining(), None);
+        assert!(test_match.projected_scores().is_empty());
     }
 
     #[test]
-    fn test_parse_scorecard_one_innings() {
-        let fp = format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_scorecard_one_innings.txt"
+    fn test_chase() {
+        let match_info = |name: &str| load_match(name, None);
+
+        // SL 41/1 in 7.2 overs chasing 258
+        let chase = match_info("cricbuzz_odi_second_innings.json")
+            .chase()
+            .unwrap();
+        assert_eq!(
+            chase,
+            Chase {
+                target: 258,
+                runs_needed: 217,
+                balls_remaining: Some(256),
+                wickets_in_hand: 9,
+                current_run_rate: 5.59,
+                required_run_rate: Some(5.09),
+            }
         );
-        // This unwrap ideally will not panic
-        let html = fs::read_to_string(fp).unwrap();
 
-        let mut scorecard = vec![];
+        // ENG 56/2 chasing 273 with 43 overs left on the last day
+        let chase = match_info("cricbuzz_test_fourth_innings.json")
+            .chase()
+            .unwrap();
+        assert_eq!(chase.runs_needed, 217);
+        assert_eq!(chase.balls_remaining, Some(258));
+        assert_eq!(chase.wickets_in_hand, 8);
+        assert_eq!(
+            chase.required_run_rate.map(|rrr| format!("{:.2}", rrr)),
+            Some("5.05".to_string())
+        );
 
-        parse_scorecard(&html, &mut scorecard);
+        assert!(match_info("cricbuzz_odi_first_innings.json")
+            .chase()
+            .is_none());
+        assert!(match_info("cricbuzz_test_second_innings.json")
+            .chase()
+            .is_none());
+        assert!(match_info("cricbuzz_odi_complete.json").chase().is_none());
+    }
 
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
+    #[test]
+    fn test_test_match_chase() {
+        const DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+        let mut mi = load_match("cricbuzz_test_fourth_innings.json", None);
+
+        // The overs left in the day are only the overs left in the match on its last day
+        mi.cricbuzz_info.match_header.day_number = Some(4);
+        let chase = mi.chase().unwrap();
+        assert_eq!(chase.runs_needed, 217);
+        assert_eq!(chase.balls_remaining, None);
+        assert_eq!(chase.required_run_rate, None);
+
+        // Day 4 is the last day of a four-day Test
+        mi.cricbuzz_info.match_header.match_complete_timestamp -= DAY_MILLIS;
+        assert_eq!(mi.cricbuzz_info.scheduled_days(), Some(4));
+        assert_eq!(mi.chase().unwrap().balls_remaining, Some(258));
+
+        // A Test extended past its scheduled days
+        mi.cricbuzz_info.match_header.day_number = Some(5);
+        assert_eq!(mi.chase().unwrap().balls_remaining, Some(258));
+
+        // The last day of a timeless Test is not known
+        mi.cricbuzz_info.match_header.match_complete_timestamp = 0;
+        let chase = mi.chase().unwrap();
+        assert_eq!(chase.balls_remaining, None);
+        assert_eq!(chase.required_run_rate, None);
+    }
+
+    #[test]
+    fn test_over_history_wickets() {
+        let mut mi = load_match("cricbuzz_odi_second_innings.json", None);
 
-        settings.bind(|| {
-            insta::assert_debug_snapshot!(scorecard);
+        // Over 4.6 of the SL innings ended with a wicket. "2 0 1 0 0 W "
+        let over = |mi: &MatchInfo, over: u32| {
+            mi.over_history
+                .iter()
+                .find(|o| o.innings_id == 2 && o.over == over)
+                .cloned()
+                .unwrap()
+        };
+        let fifth = over(&mi, 5);
+        assert_eq!((fifth.runs, fifth.wickets, fifth.score), (3, 1, 30));
+        assert_eq!(over(&mi, 6).wickets, 0);
+        assert_eq!(over(&mi, 7).wickets, 0);
+
+        // Wides are not wickets, but wickets off extras are
+        assert!(!is_wicket_ball("Wd"));
+        assert!(!is_wicket_ball("4Wd"));
+        assert!(is_wicket_ball("1W"));
+        assert!(is_wicket_ball("Wd+W"));
+
+        // A run out off a wide in over 6 that only shows up in the total wickets
+        let run_out = |sep: &mut CricbuzzOverSeparator| {
+            if sep.innings_id == 2 && sep.over_num > 5.0 {
+                sep.wickets += 1;
+            }
+        };
+        mi.cricbuzz_info.commentary_list.iter_mut().for_each(|c| {
+            if let Some(sep) = &mut c.over_separator {
+                run_out(sep);
+            }
         });
+        mi.cricbuzz_info
+            .miniscore
+            .over_summary_list
+            .iter_mut()
+            .for_each(run_out);
+        mi.update_over_history();
+        assert_eq!(over(&mi, 5).wickets, 1);
+        assert_eq!(over(&mi, 6).wickets, 1);
+        assert_eq!(over(&mi, 7).wickets, 0);
     }
 
-    #[test]
-    fn test_parse_scorecard_two_innings() {
-        let fp = format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_scorecard_two_innings.txt"
-        );
-        // This unwrap ideally will not panic
-        let html = fs::read_to_string(fp).unwrap();
+    #[tokio::test]
+    async fn test_app_new_with_provider() {
+        let mut app = App::new(Box::new(FileProvider), MatchFilter::default()).await;
+
+        assert_eq!(app.get_all_matches_short_names(), vec!["BAN vs SL - ODI"]);
+        assert_eq!(app.matches_info[0].cricbuzz_match_api_link, "file://36096");
+        assert_eq!(app.current_match_scorecard_info(0).len(), 1);
+
+        let update = fetch_update(
+            app.provider().as_ref(),
+            &app.request_permits(),
+            app.refresh_match_ids(),
+            app.known_match_ids(),
+        )
+        .await;
+        let invalid_idx = app.apply_update(update);
+        assert!(invalid_idx.is_empty());
+        assert_eq!(app.matches_info.len(), 1);
+    }
 
-        let mut scorecard = vec![];
+    #[tokio::test]
+    async fn test_app_new_with_filter() {
+        let filter = MatchFilter {
+            formats: vec!["T20".to_string()],
+            ..MatchFilter::default()
+        };
+        let mut app = App::new(Box::new(FileProvider), filter).await;
+        assert!(app.matches_info.is_empty());
+
+        // Filtered out matches are not fetched again
+        let update = fetch_update(
+            app.provider().as_ref(),
+            &app.request_permits(),
+            vec![],
+            app.known_match_ids(),
+        )
+        .await;
+        assert!(update.new_matches.is_empty());
+        app.apply_update(update);
+        assert!(app.matches_info.is_empty());
+    }
 
-        parse_scorecard(&html, &mut scorecard);
+    #[tokio::test]
+    async fn test_apply_update_adds_new_live_matches() {
+        let mut app = App::default();
+
+        let update = fetch_update(
+            &FileProvider,
+            &app.request_permits(),
+            vec![],
+            app.known_match_ids(),
+        )
+        .await;
+        assert_eq!(update.new_matches.len(), 1);
+        assert!(app.apply_update(update).is_empty());
+        assert_eq!(app.get_all_matches_short_names(), vec!["BAN vs SL - ODI"]);
+
+        // Closed matches stay closed while they are live
+        app.close_match(0);
+        let update = fetch_update(
+            &FileProvider,
+            &app.request_permits(),
+            vec![],
+            app.known_match_ids(),
+        )
+        .await;
+        assert!(update.new_matches.is_empty());
+    }
 
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
+    #[tokio::test(start_paused = true)]
+    async fn test_fetch_update_limits_concurrent_requests() {
+        let provider = SlowProvider::new(Duration::from_millis(100), 6);
+        let permits = Semaphore::new(MAX_CONCURRENT_REQUESTS);
+
+        // Six known matches are refreshed while six new ones are fetched
+        let update = fetch_update(&provider, &permits, (100..106).collect(), Some(vec![])).await;
+        assert_eq!(update.matches.len(), 6);
+        assert_eq!(update.new_matches.len(), 6);
+        assert!(update
+            .matches
+            .iter()
+            .all(|(_, (json, scrd))| json.is_ok() && scrd.is_ok()));
 
-        settings.bind(|| {
-            insta::assert_debug_snapshot!(scorecard);
-        });
+        assert_eq!(
+            provider.max_in_flight.load(Ordering::SeqCst),
+            MAX_CONCURRENT_REQUESTS
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fetch_gives_up_after_timeout() {
+        let provider = SlowProvider::new(FETCH_TIMEOUT + Duration::from_secs(1), 1);
+        let permits = Semaphore::new(MAX_CONCURRENT_REQUESTS);
+
+        let (json, scorecard) = fetch_match(&provider, &permits, 36096).await;
+        assert!(matches!(json, Err(ProviderError::Timeout(t)) if t == FETCH_TIMEOUT));
+        assert!(matches!(scorecard, Err(ProviderError::Timeout(_))));
+
+        let update = fetch_update(&provider, &permits, vec![36096], Some(vec![])).await;
+        assert!(matches!(
+            update.live_match_ids,
+            Err(ProviderError::Timeout(_))
+        ));
+
+        // Requests which answer in time are not affected
+        let provider = SlowProvider::new(FETCH_TIMEOUT - Duration::from_secs(1), 1);
+        let (json, scorecard) = fetch_match(&provider, &permits, 36096).await;
+        assert!(json.is_ok() && scorecard.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_app_new_with_match_ids() {
+        let mut app = App::new_with_match_ids(Box::new(FileProvider), vec![7, 36096]).await;
+        assert_eq!(app.match_ids(), vec![7, 36096]);
+        assert_eq!(app.known_match_ids(), None);
+
+        // The chosen matches are kept even though only one of them is live
+        let update = fetch_update(
+            app.provider().as_ref(),
+            &app.request_permits(),
+            app.refresh_match_ids(),
+            app.known_match_ids(),
+        )
+        .await;
+        assert!(update.new_matches.is_empty());
+        assert!(app.apply_update(update).is_empty());
+        assert_eq!(app.match_ids(), vec![7, 36096]);
     }
 
     #[test]
-    fn test_parse_scorecard_three_innings() {
-        let fp = format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_scorecard_three_innings.txt"
-        );
-        // This unwrap ideally will not panic
-        let html = fs::read_to_string(fp).unwrap();
+    fn test_favourite_teams_are_shown_first() {
+        let mut app = App::default();
+        for file in [
+            "cricbuzz_odi_first_innings.json",
+            "cricbuzz_test_first_innings.json",
+        ] {
+            app.matches_info.push(load_match(file, None));
+        }
 
-        let mut scorecard = vec![];
+        app.set_favourite_teams(vec!["nz".to_string()]);
+        assert_eq!(app.matches_info[0].match_short_name, "ENG vs NZ");
+        assert!(app.is_favourite(0));
+        assert!(!app.is_favourite(1));
+    }
 
-        parse_scorecard(&html, &mut scorecard);
+    #[tokio::test]
+    async fn test_apply_update_removes_finished_matches() {
+        let mut app = App::new(Box::new(FileProvider), MatchFilter::default()).await;
+        for match_id in [1, 2] {
+            let json = FileProvider.match_info(match_id).await.unwrap();
+            app.matches_info.push(create_match_info(
+                "BAN vs SL".to_string(),
+                match_id,
+                "".to_string(),
+                json,
+                vec![],
+            ));
+        }
 
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
+        let invalid_idx = app.apply_update(AppUpdate {
+            live_match_ids: Ok(vec![2]),
+            matches: vec![],
+            new_matches: vec![],
+        });
+        assert_eq!(invalid_idx, vec![1, 0]);
+        assert_eq!(app.match_ids(), vec![2]);
+    }
 
-        settings.bind(|| {
-            insta::assert_debug_snapshot!(scorecard);
+    #[tokio::test]
+    async fn test_apply_update_keeps_matches_on_error() {
+        let mut app = App::new(Box::new(FileProvider), MatchFilter::default()).await;
+        assert!(app.last_error.is_none());
+
+        let invalid_idx = app.apply_update(AppUpdate {
+            live_match_ids: Err(ProviderError::Timeout(Duration::from_secs(10))),
+            matches: vec![(
+                36096,
+                (
+                    Err(ProviderError::Unavailable("No details".to_string())),
+                    Ok(vec![]),
+                ),
+            )],
+            new_matches: vec![],
         });
+        assert!(invalid_idx.is_empty());
+        assert_eq!(app.current_match_scorecard_info(0).len(), 1);
+
+        let err = app.last_error.unwrap();
+        assert_eq!(err.match_id, None);
+        assert_eq!(err.error.to_string(), "Request timed out after 10s");
+    }
+
+    #[tokio::test]
+    async fn test_apply_update_keeps_scorecard_on_error() {
+        let mut app = App::new(Box::new(FileProvider), MatchFilter::default()).await;
+        let json = load_json("cricbuzz_odi_second_innings.json");
+
+        // The details are applied even though the scorecard could not be fetched
+        app.apply_update(AppUpdate {
+            live_match_ids: Ok(vec![36096]),
+            matches: vec![(
+                36096,
+                (
+                    Ok(json),
+                    Err(ProviderError::Layout("No innings found".to_string())),
+                ),
+            )],
+            new_matches: vec![],
+        });
+        assert_eq!(app.current_match_cricbuzz_info(0).miniscore.innings_id, 2);
+        assert_eq!(app.current_match_scorecard_info(0).len(), 1);
+        let err = app.last_error.take().unwrap();
+        assert_eq!(err.match_id, Some(36096));
+        assert!(app.matches_info[0].refresh_failed);
+
+        // The tab is marked until the match is refreshed in full
+        let fetched = fetch_match(&FileProvider, &app.request_permits(), 36096).await;
+        app.apply_update(AppUpdate {
+            live_match_ids: Ok(vec![36096]),
+            matches: vec![(36096, fetched)],
+            new_matches: vec![],
+        });
+        assert!(!app.matches_info[0].refresh_failed);
+        app.apply_update(AppUpdate {
+            live_match_ids: Ok(vec![36096]),
+            matches: vec![(
+                36096,
+                (Err(ProviderError::Timeout(FETCH_TIMEOUT)), Ok(vec![])),
+            )],
+            new_matches: vec![],
+        });
+        assert!(app.matches_info[0].refresh_failed);
+        app.last_error = None;
+
+        // Matches which have not started have no innings on their scorecard
+        let json = load_json("cricbuzz_odi_pre_toss.json");
+        app.apply_update(AppUpdate {
+            live_match_ids: Ok(vec![36096, 7]),
+            matches: vec![],
+            new_matches: vec![(
+                LiveMatch {
+                    short_name: "BAN vs SL".to_string(),
+                    match_id: 7,
+                },
+                (
+                    Ok(json),
+                    Err(ProviderError::Layout("No innings found".to_string())),
+                ),
+            )],
+        });
+        assert_eq!(app.matches_info.len(), 2);
+        assert!(app.current_match_scorecard_info(1).is_empty());
+        assert!(app.last_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_opened_match_is_kept_when_not_live() {
+        let mut app = App::new(Box::new(FileProvider), MatchFilter::default()).await;
+        let entry = BrowserEntry {
+            short_name: "NED vs SCO".to_string(),
+            match_id: 36092,
+            description: "".to_string(),
+            status: BrowserStatus::Completed("SCO Won".to_string()),
+        };
+
+        let opened = fetch_opened_match(&FileProvider, &app.request_permits(), &entry).await;
+        assert_eq!(app.open_match(opened), Some(1));
+
+        let update = fetch_update(
+            app.provider().as_ref(),
+            &app.request_permits(),
+            app.refresh_match_ids(),
+            app.known_match_ids(),
+        )
+        .await;
+        assert_eq!(update.matches.len(), 2);
+        assert!(app.apply_update(update).is_empty());
+        assert_eq!(app.match_ids(), vec![36096, 36092]);
     }
 
     #[test]
-    fn test_parse_scorecard_four_innings() {
-        let fp = format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_scorecard_four_innings.txt"
+    fn test_browser_entry_status() {
+        let listed = || ListedMatch {
+            short_name: "BAN vs SL".to_string(),
+            match_id: 36096,
+            status: "Preview".to_string(),
+        };
+
+        let json = load_json("cricbuzz_odi_pre_toss.json");
+        let entry = BrowserEntry::new(listed(), &json).unwrap();
+        assert_eq!(entry.status, BrowserStatus::Upcoming(1621753200000));
+        assert_eq!(
+            entry.description,
+            "1st ODI, Sri Lanka tour of Bangladesh, 2021"
+        );
+
+        let mut json = load_json("cricbuzz_odi_first_innings.json");
+        assert!(BrowserEntry::new(listed(), &json).is_none());
+
+        json.match_header.complete = true;
+        json.match_header.status = "Bangladesh won by 33 runs".to_string();
+        assert_eq!(
+            BrowserEntry::new(listed(), &json).unwrap().status,
+            BrowserStatus::Completed("Bangladesh won by 33 runs".to_string())
         );
-        // This unwrap ideally will not panic
-        let html = fs::read_to_string(fp).unwrap();
+    }
 
-        let mut scorecard = vec![];
+    #[tokio::test]
+    async fn test_apply_update_keeps_completed_matches() {
+        let mut app = App::new(Box::new(FileProvider), MatchFilter::default()).await;
+        let json = load_json("cricbuzz_odi_complete.json");
 
-        parse_scorecard(&html, &mut scorecard);
+        app.track_diffs();
+        let invalid_idx = app.apply_update(AppUpdate {
+            live_match_ids: Ok(vec![]),
+            matches: vec![(36096, (Ok(json), Ok(vec![])))],
+            new_matches: vec![],
+        });
+        assert!(invalid_idx.is_empty());
+        assert_eq!(
+            app.get_all_matches_short_names(),
+            vec!["BAN vs SL - Result"]
+        );
+        assert!(app.refresh_match_ids().is_empty());
+
+        let alerts = app.take_alerts();
+        assert_eq!(alerts.last().map(|a| a.kind), Some(AlertKind::Result));
+        assert!(app.take_alerts().is_empty());
+        let diffs = app.take_diffs();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(
+            diffs[0].status.as_deref(),
+            Some("Bangladesh won by 33 runs")
+        );
+        assert!(app.take_diffs().is_empty());
 
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
+        app.close_match(0);
+        assert!(app.matches_info.is_empty());
+    }
 
-        settings.bind(|| {
-            insta::assert_debug_snapshot!(scorecard);
+    #[tokio::test]
+    async fn test_close_completed_matches() {
+        let mut app = App::new(Box::new(FileProvider), MatchFilter::default()).await;
+        let json = load_json("cricbuzz_odi_complete.json");
+        app.apply_update(AppUpdate {
+            live_match_ids: Ok(vec![]),
+            matches: vec![(36096, (Ok(json), Ok(vec![])))],
+            new_matches: vec![],
         });
+
+        // The result is kept during the grace period
+        let grace = Duration::from_secs(3600);
+        app.close_completed_matches(grace);
+        assert_eq!(app.matches_info.len(), 1);
+
+        let finished = app.matches_info[0].last_refresh - grace * 2;
+        app.matches_info[0].last_refresh = finished;
+        app.matches_info[0].pinned = true;
+        app.close_completed_matches(grace);
+        assert_eq!(app.matches_info.len(), 1);
+
+        app.matches_info[0].pinned = false;
+        app.close_completed_matches(grace);
+        assert!(app.matches_info.is_empty());
     }
 }
@@ -0,0 +1,201 @@
+//! # CLI
+//!
+//! The command line of the `cricket-rs` binary. Flags given on the command line take
+//! precedence over the config file.
+
+use std::{error::Error, net::SocketAddr, num::NonZeroU64, path::PathBuf};
+
+use clap::{crate_version, value_parser, Arg, ArgAction, Command};
+
+use crate::{
+    app::App,
+    config::Config,
+    display::{
+        output::{format_matches, OutputFormat},
+        terminal,
+    },
+    provider::{CricbuzzProvider, Recorder, ReplayProvider, ScoreProvider},
+    server,
+};
+
+/// Parses the command line and runs the terminal UI, prints the scores once or serves them
+/// as JSON, as asked for. This is all the `cricket-rs` binary does.
+pub async fn run_cli() -> Result<(), Box<dyn Error>> {
+    let matches = Command::new("cricket-rs")
+        .author("Prithvi MK <prithvikrishna49 AT gmail DOT com>")
+        .version(crate_version!())
+        .about("Fast and optimized live cricket score viewer in the terminal")
+        .arg(
+            Arg::new("tick-rate")
+                .global(true)
+                .short('t')
+                .long("tick-rate")
+                .value_name("MILLISECONDS")
+                .help("Sets match details refresh rate [default: 40000]")
+                .value_parser(value_parser!(u64).range(1..))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("match-id")
+                .global(true)
+                .short('m')
+                .long("match-id")
+                .value_name("ID")
+                .help("IDs of the matches to follow, whether they are live or not. Eg. 36096,36092")
+                .value_parser(value_parser!(u32))
+                .value_delimiter(',')
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("config")
+                .global(true)
+                .short('c')
+                .long("config")
+                .value_name("FILE")
+                .help("Reads settings from a TOML file instead of the default config file")
+                .value_parser(value_parser!(PathBuf))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("team")
+                .global(true)
+                .long("team")
+                .value_name("TEAM")
+                .help("Only shows matches of these teams, by short name. Eg. IND,AUS")
+                .value_delimiter(',')
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("match-format")
+                .global(true)
+                .long("match-format")
+                .value_name("FORMAT")
+                .help("Only shows matches of these formats. Eg. T20,ODI")
+                .value_delimiter(',')
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("series")
+                .global(true)
+                .long("series")
+                .value_name("SERIES")
+                .help("Only shows matches of series whose name contains this text")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("international-only")
+                .global(true)
+                .long("international-only")
+                .help("Leaves out domestic matches")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("replay")
+                .global(true)
+                .long("replay")
+                .value_name("DIR")
+                .help("Replays recorded match snapshots from a directory instead of going online")
+                .value_parser(value_parser!(PathBuf))
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("record")
+                .global(true)
+                .long("record")
+                .value_name("DIR")
+                .help("Records every response fetched from Cricbuzz to a directory")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("replay")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("once")
+                .long("once")
+                .help("Prints the live scores to stdout once and exits")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Output format of --once")
+                .default_value("plain")
+                .value_parser(["plain", "json"])
+                .requires("once")
+                .action(ArgAction::Set),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Refreshes the matches without a UI and serves them as JSON over HTTP")
+                .arg(
+                    Arg::new("addr")
+                        .short('a')
+                        .long("addr")
+                        .value_name("ADDRESS")
+                        .help("Address to listen on")
+                        .default_value("127.0.0.1:8080")
+                        .value_parser(value_parser!(SocketAddr))
+                        .action(ArgAction::Set),
+                ),
+        )
+        .get_matches();
+
+    let mut config = Config::load(matches.get_one::<PathBuf>("config").map(PathBuf::as_path))?;
+    // Filters given on the command line replace the ones from the config file
+    for (arg, filter) in [
+        ("team", &mut config.filters.teams),
+        ("match-format", &mut config.filters.formats),
+        ("series", &mut config.filters.series),
+    ] {
+        if let Some(values) = matches.get_many::<String>(arg) {
+            *filter = values.cloned().collect();
+        }
+    }
+    if matches.get_flag("international-only") {
+        config.filters.international_only = true;
+    }
+    let tick_rate = matches
+        .get_one::<u64>("tick-rate")
+        .copied()
+        .or(config.tick_rate.map(NonZeroU64::get))
+        .unwrap_or(40000);
+    let match_ids: Vec<u32> = matches
+        .get_many::<u32>("match-id")
+        .map(|ids| ids.copied().collect())
+        .unwrap_or_default();
+
+    let provider: Box<dyn ScoreProvider> = match matches.get_one::<PathBuf>("replay") {
+        Some(dir) => Box::new(ReplayProvider::new(dir)?),
+        None => match matches.get_one::<PathBuf>("record") {
+            Some(dir) => Box::new(CricbuzzProvider::new_with_recorder(Recorder::new(dir)?)),
+            None => Box::new(CricbuzzProvider::new()),
+        },
+    };
+    let mut app = if match_ids.is_empty() {
+        App::new(provider, config.filters.clone()).await
+    } else {
+        App::new_with_match_ids(provider, match_ids).await
+    };
+
+    app.set_favourite_teams(config.favourite_teams.clone());
+
+    if let Some(serve_matches) = matches.subcommand_matches("serve") {
+        // The address has a default value
+        let addr = *serve_matches.get_one::<SocketAddr>("addr").unwrap();
+        return server::serve(app, addr, tick_rate).await;
+    }
+
+    if matches.get_flag("once") {
+        let format = matches
+            .get_one::<String>("format")
+            .and_then(|f| OutputFormat::from_name(f))
+            .unwrap_or(OutputFormat::Plain);
+        println!("{}", format_matches(&app, format));
+        if let Some(err) = &app.last_error {
+            eprintln!("{}", err.error);
+        }
+        return Ok(());
+    }
+
+    terminal::run(app, tick_rate, config)
+}
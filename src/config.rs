@@ -0,0 +1,441 @@
+//! # Config
+//!
+//! Settings read from a TOML file, so a standard setup can be shared instead of repeating
+//! command line flags. Flags given on the command line take precedence over the file.
+//!
+//! The file is looked up at `$XDG_CONFIG_HOME/cricket-rs/config.toml` (usually
+//! `~/.config/cricket-rs/config.toml`) unless another path is passed with `--config`.
+//!
+//! ```toml
+//! tick_rate = 30000
+//! favourite_teams = ["IND", "NZ"]
+//!
+//! [filters]
+//! formats = ["ODI", "T20"]
+//! international_only = true
+//!
+//! [theme]
+//! highlight = "light_green"
+//! favourite = "#ffaf00"
+//!
+//! [layout]
+//! commentary_height = 12
+//! default_view = "worm"
+//!
+//! [keys]
+//! refresh = ["f5"]
+//!
+//! [alerts]
+//! bell = true
+//! command = "notify-send \"$CRICKET_MATCH\" \"$CRICKET_DESCRIPTION\""
+//! ```
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt, fs, io,
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Deserializer};
+use tui::style::Color;
+
+use crate::{
+    alert::AlertKind,
+    cricbuzz_api::CricbuzzJson,
+    display::ui::MatchView,
+    event::{Action, Key},
+};
+
+/// All the settings which can be set in the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Match details refresh rate in milliseconds. Zero is rejected, as it would refresh
+    /// back to back.
+    pub tick_rate: Option<NonZeroU64>,
+    /// Which matches are shown
+    pub filters: MatchFilter,
+    /// Short names of the teams whose matches are shown first. Eg. IND
+    pub favourite_teams: Vec<String>,
+    pub theme: Theme,
+    pub layout: PaneLayout,
+    /// Keys replacing the default keys of an action
+    pub keys: HashMap<Action, Vec<Key>>,
+    pub alerts: AlertConfig,
+}
+
+/// Decides which matches are shown. Every condition that is set has to hold.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MatchFilter {
+    /// Short names of the teams, one of which has to be playing. Eg. IND
+    pub teams: Vec<String>,
+    /// Formats of the match. Eg. T20
+    pub formats: Vec<String>,
+    /// Text that has to appear in the name of the series, ignoring case
+    pub series: Vec<String>,
+    /// Leaves out domestic matches
+    pub international_only: bool,
+}
+
+/// Colours used by the UI
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// Titles of the tabs
+    #[serde(deserialize_with = "deserialize_color")]
+    pub tab: Color,
+    /// Title of the selected tab
+    #[serde(deserialize_with = "deserialize_color")]
+    pub highlight: Color,
+    /// Titles of the tabs of favourite teams' matches
+    #[serde(deserialize_with = "deserialize_color")]
+    pub favourite: Color,
+    /// Match status below the scores. Eg. "Sri Lanka need 217 runs"
+    #[serde(deserialize_with = "deserialize_color")]
+    pub status: Color,
+    /// Batsmen and bowlers in the live feed
+    #[serde(deserialize_with = "deserialize_color")]
+    pub text: Color,
+}
+
+/// What is done when something happens in a match
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertConfig {
+    /// Alerts which are acted upon, all of them except fours and sixes if empty.
+    /// Eg. ["wicket", "six", "result"]
+    #[serde(deserialize_with = "deserialize_alert_kinds")]
+    pub events: Vec<AlertKind>,
+    /// Rings the terminal bell
+    pub bell: bool,
+    /// Flashes the title of the tab until it is selected
+    pub flash: bool,
+    /// Shell command run with the details of the alert in environment variables
+    pub command: Option<String>,
+}
+
+impl AlertConfig {
+    /// Returns whether an alert is acted upon
+    pub fn is_enabled(&self, kind: AlertKind) -> bool {
+        if self.events.is_empty() {
+            // Boundaries are too frequent to be acted upon unless they are asked for
+            !matches!(kind, AlertKind::Four | AlertKind::Six)
+        } else {
+            self.events.contains(&kind)
+        }
+    }
+}
+
+impl Default for AlertConfig {
+    fn default() -> AlertConfig {
+        AlertConfig {
+            events: vec![],
+            bell: false,
+            flash: true,
+            command: None,
+        }
+    }
+}
+
+/// Sizes and contents of the panes of a match tab
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaneLayout {
+    /// Height of the pane with the batsmen, bowlers and key stats
+    pub live_feed_height: u16,
+    /// Height of the commentary pane, which is hidden if it is 0
+    pub commentary_height: u16,
+    /// View shown below the commentary when the app starts
+    #[serde(deserialize_with = "deserialize_match_view")]
+    pub default_view: MatchView,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            tab: Color::White,
+            highlight: Color::Green,
+            favourite: Color::Yellow,
+            status: Color::LightRed,
+            text: Color::White,
+        }
+    }
+}
+
+impl Default for PaneLayout {
+    fn default() -> PaneLayout {
+        PaneLayout {
+            live_feed_height: 9,
+            commentary_height: 8,
+            default_view: MatchView::Scorecard,
+        }
+    }
+}
+
+/// Error returned when the config file cannot be used
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read
+    Io(PathBuf, io::Error),
+    /// The file is not valid TOML or has unknown settings
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "Cannot read {}: {}", path.display(), e),
+            ConfigError::Parse(path, e) => write!(f, "Invalid config {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(_, e) => Some(e),
+            ConfigError::Parse(_, e) => Some(e),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config from a file. If no file is given, the file in the config directory
+    /// is read if there is one, otherwise the defaults are used.
+    pub fn load(path: Option<&Path>) -> Result<Config, ConfigError> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Config::default()),
+            },
+        };
+
+        let text = fs::read_to_string(&path).map_err(|e| ConfigError::Io(path.clone(), e))?;
+        Config::from_toml_str(&text).map_err(|e| ConfigError::Parse(path, e))
+    }
+
+    /// Parses the config from the contents of a TOML file
+    pub fn from_toml_str(text: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(text)
+    }
+}
+
+/// Returns the path of the config file in the config directory of the user
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cricket-rs").join("config.toml"))
+}
+
+impl MatchFilter {
+    /// Returns whether a match should be shown
+    pub fn matches(&self, json: &CricbuzzJson) -> bool {
+        let header = &json.match_header;
+
+        let team_ok = self.teams.is_empty()
+            || self.teams.iter().any(|t| {
+                t.eq_ignore_ascii_case(&header.team1.short_name)
+                    || t.eq_ignore_ascii_case(&header.team2.short_name)
+            });
+        let format_ok = self.formats.is_empty()
+            || self
+                .formats
+                .iter()
+                .any(|f| f.eq_ignore_ascii_case(&header.match_format));
+        let series_name = header.series_name.to_lowercase();
+        let series_ok = self.series.is_empty()
+            || self
+                .series
+                .iter()
+                .any(|s| series_name.contains(&s.to_lowercase()));
+        let international_ok = !self.international_only || !header.domestic;
+
+        team_ok && format_ok && series_ok && international_ok
+    }
+}
+
+/// Parses a colour from its name, eg. "light_red", or its hex code, eg. "#ffaf00"
+fn parse_color(text: &str) -> Option<Color> {
+    let color = match text.to_lowercase().replace('-', "_").as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "dark_gray" | "dark_grey" => Color::DarkGray,
+        "light_red" => Color::LightRed,
+        "light_green" => Color::LightGreen,
+        "light_yellow" => Color::LightYellow,
+        "light_blue" => Color::LightBlue,
+        "light_magenta" => Color::LightMagenta,
+        "light_cyan" => Color::LightCyan,
+        "white" => Color::White,
+        hex => {
+            let hex = hex.strip_prefix('#')?;
+            // Slicing by bytes needs every character to be a single byte
+            if hex.len() != 6 || !hex.is_ascii() {
+                return None;
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+            Color::Rgb(channel(0)?, channel(2)?, channel(4)?)
+        }
+    };
+    Some(color)
+}
+
+fn deserialize_color<'de, D>(deserializer: D) -> Result<Color, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    parse_color(&text)
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown colour \"{}\"", text)))
+}
+
+fn deserialize_alert_kinds<'de, D>(deserializer: D) -> Result<Vec<AlertKind>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let names = Vec::<String>::deserialize(deserializer)?;
+    names
+        .iter()
+        .map(|name| {
+            AlertKind::from_name(name).ok_or_else(|| {
+                serde::de::Error::custom(format!(
+                    "unknown alert \"{}\", expected wicket, four, six, fifty, hundred, \
+                     five_for or result",
+                    name
+                ))
+            })
+        })
+        .collect()
+}
+
+fn deserialize_match_view<'de, D>(deserializer: D) -> Result<MatchView, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    match text.as_str() {
+        "scorecard" => Ok(MatchView::Scorecard),
+        "worm" => Ok(MatchView::Worm),
+        "manhattan" => Ok(MatchView::Manhattan),
+        _ => Err(serde::de::Error::custom(format!(
+            "unknown view \"{}\", expected scorecard, worm or manhattan",
+            text
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use tui::style::Color;
+
+    use crate::{
+        alert::AlertKind,
+        config::{parse_color, AlertConfig, Config, MatchFilter},
+        display::ui::MatchView,
+        event::{Action, Key},
+        test_utils::load_json,
+    };
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::from_toml_str(
+            r##"
+            tick_rate = 30000
+            favourite_teams = ["IND"]
+
+            [filters]
+            formats = ["T20"]
+
+            [theme]
+            highlight = "light_green"
+            favourite = "#ffaf00"
+
+            [layout]
+            commentary_height = 0
+            default_view = "manhattan"
+
+            [keys]
+            quit = ["esc", "ctrl+c"]
+
+            [alerts]
+            events = ["wicket", "six", "five_for"]
+            command = "echo $CRICKET_EVENT"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(config.tick_rate.map(NonZeroU64::get), Some(30000));
+        assert_eq!(config.favourite_teams, vec!["IND"]);
+        assert_eq!(config.filters.formats, vec!["T20"]);
+        assert_eq!(config.theme.highlight, Color::LightGreen);
+        assert_eq!(config.theme.favourite, Color::Rgb(0xff, 0xaf, 0x00));
+        assert_eq!(config.theme.tab, Color::White);
+        assert_eq!(config.layout.commentary_height, 0);
+        assert_eq!(config.layout.live_feed_height, 9);
+        assert_eq!(config.layout.default_view, MatchView::Manhattan);
+        assert_eq!(config.keys[&Action::Quit], vec![Key::Esc, Key::Ctrl('c')]);
+        assert!(config.alerts.is_enabled(AlertKind::FiveFor));
+        assert!(config.alerts.is_enabled(AlertKind::Six));
+        assert!(!config.alerts.is_enabled(AlertKind::Four));
+        assert!(!config.alerts.is_enabled(AlertKind::Result));
+        assert!(config.alerts.flash);
+        assert_eq!(
+            config.alerts.command.as_deref(),
+            Some("echo $CRICKET_EVENT")
+        );
+
+        assert!(Config::from_toml_str("tick_rat = 1").is_err());
+        assert!(Config::from_toml_str("tick_rate = 0").is_err());
+        assert!(Config::from_toml_str("[theme]\ntab = \"#12345\"").is_err());
+        assert!(Config::from_toml_str("[keys]\nfly = [\"f\"]").is_err());
+        assert!(Config::from_toml_str("[alerts]\nevents = [\"maiden\"]").is_err());
+
+        // Boundaries are only acted upon when they are asked for
+        let alerts = AlertConfig::default();
+        assert!(alerts.is_enabled(AlertKind::Wicket));
+        assert!(!alerts.is_enabled(AlertKind::Four));
+        assert!(!alerts.is_enabled(AlertKind::Six));
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("Light-Cyan"), Some(Color::LightCyan));
+        assert_eq!(parse_color("#FFA500"), Some(Color::Rgb(255, 165, 0)));
+        assert_eq!(parse_color("#ffa50"), None);
+        assert_eq!(parse_color("#ffa5zz"), None);
+        assert_eq!(parse_color("orange"), None);
+        // Six bytes long, but not six characters
+        assert_eq!(parse_color("#a\u{20ac}bc"), None);
+    }
+
+    #[test]
+    fn test_match_filter() {
+        let json = load_json("cricbuzz_odi_first_innings.json");
+
+        assert!(MatchFilter::default().matches(&json));
+
+        let filter = MatchFilter {
+            teams: vec!["sl".to_string()],
+            formats: vec!["ODI".to_string()],
+            series: vec!["tour of bangladesh".to_string()],
+            international_only: true,
+        };
+        assert!(filter.matches(&json));
+
+        let filter = MatchFilter {
+            formats: vec!["T20".to_string()],
+            ..MatchFilter::default()
+        };
+        assert!(!filter.matches(&json));
+    }
+}
@@ -1,110 +1,200 @@
 //! # Cricbuzz API Response Format
 //!
 //! This file just defines the format of the response JSON from Cricbuzz's API.
-//! Also contains a few helper methods to access certain fields.
+//! Also contains a few helper methods to access certain fields. The types serialise back
+//! into the same format, which is how the miniscore is served by the `serve` subcommand.
 
-use serde::Deserialize;
+use std::{collections::HashMap, convert::TryFrom};
 
-/* -- Not Used -- */
-// #[derive(Debug, Deserialize)]
-// #[serde(rename_all = "camelCase")]
-// pub struct CricbuzzOverSeparator {
-//     pub score: u32,
-//     pub wickets: u32,
-//     pub innings_id: u32,
-//     pub o_summary: String,
-//     pub runs: u32,
-//     pub bat_striker_ids: Vec<u32>,
-//     pub bat_striker_names: Vec<String>,
-//     pub bat_striker_runs: u32,
-//     pub bat_striker_balls: u32,
-//     pub bat_non_striker_ids: Vec<u32>,
-//     pub bat_non_striker_names: Vec<String>,
-//     pub bat_non_striker_runs: u32,
-//     pub bat_non_striker_balls: u32,
-//     pub bowl_ids: Vec<u32>,
-//     pub bowl_names: Vec<String>,
-//     pub bowl_overs: f32,
-//     pub bowl_maidens: u32,
-//     pub bowl_runs: u32,
-//     pub bowl_wickets: u32,
-//     pub timestamp: u64,
-//     pub over_num: f32,
-//     pub bat_team_name: String,
-//     pub event: String,
-// }
+use serde::{Deserialize, Serialize};
 
-/* -- Not Used -- */
-// #[derive(Debug, Deserialize)]
-// #[serde(rename_all = "camelCase")]
-// pub struct CricbuzzBatsmanStriker {
-//     pub bat_balls: u32,
-//     pub bat_dots: u32,
-//     pub bat_fours: u32,
-//     pub bat_id: u32,
-//     pub bat_name: String,
-//     pub bat_mins: u32,
-//     pub bat_runs: u32,
-//     pub bat_sixes: u32,
-//     pub bat_strike_rate: f32,
-// }
+/// Summary of an over, sent with the commentary entry at the end of every over.
+/// Fields are defaulted since only a few of them are needed.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CricbuzzOverSeparator {
+    pub score: u32,
+    pub wickets: u32,
+    pub innings_id: u32,
+    /// Runs off each ball of the over. Eg. "2 0 1 0 Wd W "
+    #[serde(rename = "o_summary")]
+    pub o_summary: String,
+    pub runs: u32,
+    pub bat_striker_ids: Vec<u32>,
+    pub bat_striker_names: Vec<String>,
+    pub bat_striker_runs: u32,
+    pub bat_striker_balls: u32,
+    pub bat_non_striker_ids: Vec<u32>,
+    pub bat_non_striker_names: Vec<String>,
+    pub bat_non_striker_runs: u32,
+    pub bat_non_striker_balls: u32,
+    pub bowl_ids: Vec<u32>,
+    pub bowl_names: Vec<String>,
+    pub bowl_overs: f32,
+    pub bowl_maidens: u32,
+    pub bowl_runs: u32,
+    pub bowl_wickets: u32,
+    pub timestamp: u64,
+    pub over_num: f32,
+    pub bat_team_name: String,
+    pub event: String,
+}
 
-/* -- Not Used -- */
-// #[derive(Debug, Deserialize)]
-// #[serde(rename_all = "camelCase")]
-// pub struct CricbuzzBowlerStriker {
-//     pub bowl_id: u32,
-//     pub bowl_name: String,
-//     pub bowl_maidens: u32,
-//     pub bowl_noballs: u32,
-//     pub bowl_ovs: f32,
-//     pub bowl_runs: u32,
-//     pub bowl_wides: u32,
-//     pub bowl_wkts: u32,
-//     pub bowl_econ: f32,
-// }
+/// Fields are defaulted since Cricbuzz omits some of them for a few commentary entries
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CricbuzzBatsmanStriker {
+    pub bat_balls: u32,
+    pub bat_dots: u32,
+    pub bat_fours: u32,
+    pub bat_id: u32,
+    pub bat_name: String,
+    pub bat_mins: u32,
+    pub bat_runs: u32,
+    pub bat_sixes: u32,
+    pub bat_strike_rate: f32,
+}
 
-/* -- Not Used -- */
-// #[derive(Debug, Deserialize)]
-// #[serde(rename_all = "camelCase")]
-// pub struct CricbuzzCommentary {
-//     pub comm_text: String,
-//     pub timestamp: u64,
-//     pub ball_nbr: u32,
-//     pub over_number: f32,
-//     pub innings_id: u32,
-//     pub event: String,
-//     pub bat_team_name: String,
-//     pub commentary_formats: Vec<String>, // Not sure about data type
-//     pub over_separator: CricbuzzOverSeparator,
-//     pub batsman_striker: CricbuzzBatsmanStriker,
-//     pub bowler_striker: CricbuzzBowlerStriker,
-// }
+/// Fields are defaulted since Cricbuzz omits some of them for a few commentary entries
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CricbuzzBowlerStriker {
+    pub bowl_id: u32,
+    pub bowl_name: String,
+    pub bowl_maidens: u32,
+    pub bowl_noballs: u32,
+    pub bowl_ovs: f32,
+    pub bowl_runs: u32,
+    pub bowl_wides: u32,
+    pub bowl_wkts: u32,
+    pub bowl_econ: f32,
+}
+
+/// Placeholders (e.g. `B0$`) in the commentary text and the values they stand for
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CricbuzzCommentaryFormat {
+    pub format_id: Vec<String>,
+    pub format_value: Vec<String>,
+}
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
+pub struct CricbuzzCommentaryFormats {
+    pub bold: Option<CricbuzzCommentaryFormat>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CricbuzzCommentary {
+    pub comm_text: String,
+    pub timestamp: u64,
+    pub ball_nbr: u32,
+    /// Not present for non-delivery entries like a new bowler or batsman announcement
+    pub over_number: Option<f32>,
+    pub innings_id: u32,
+    pub event: String,
+    pub bat_team_name: String,
+    pub commentary_formats: CricbuzzCommentaryFormats,
+    pub over_separator: Option<CricbuzzOverSeparator>,
+    pub batsman_striker: Option<CricbuzzBatsmanStriker>,
+    pub bowler_striker: Option<CricbuzzBowlerStriker>,
+}
+
+impl CricbuzzCommentary {
+    /// Returns the commentary text split into segments, with the bold placeholders
+    /// substituted by their values. The boolean is `true` for bold segments.
+    pub fn comm_text_segments(&self) -> Vec<(String, bool)> {
+        let mut segments = vec![];
+        let mut text = self.comm_text.trim();
+
+        let bold = match &self.commentary_formats.bold {
+            Some(b) => b,
+            None => return vec![(text.to_string(), false)],
+        };
+
+        loop {
+            // Find the earliest placeholder that occurs in the remaining text. An empty
+            // placeholder matches everywhere without consuming any text, so it is skipped.
+            let next = bold
+                .format_id
+                .iter()
+                .zip(bold.format_value.iter())
+                .filter(|(id, _)| !id.is_empty())
+                .filter_map(|(id, value)| text.find(id.as_str()).map(|pos| (pos, id, value)))
+                .min_by_key(|(pos, _, _)| *pos);
+
+            match next {
+                Some((pos, id, value)) => {
+                    if pos > 0 {
+                        segments.push((text[..pos].to_string(), false));
+                    }
+                    segments.push((value.to_string(), true));
+                    text = &text[pos + id.len()..];
+                }
+                None => {
+                    if !text.is_empty() {
+                        segments.push((text.to_string(), false));
+                    }
+                    break;
+                }
+            }
+        }
+
+        segments
+    }
+
+    /// Returns `true` if the delivery was a boundary four
+    pub fn is_four(&self) -> bool {
+        self.event.split(',').any(|e| e == "FOUR")
+    }
+
+    /// Returns `true` if the delivery was a six
+    pub fn is_six(&self) -> bool {
+        self.event.split(',').any(|e| e == "SIX")
+    }
+
+    /// Returns `true` if a wicket fell on the delivery
+    pub fn is_wicket(&self) -> bool {
+        self.event.split(',').any(|e| e == "WICKET")
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMatchHeaderTossResults {
     pub toss_winner_id: u32,
     pub toss_winner_name: String,
     pub decision: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMatchHeaderResults {
+    pub result_type: String,
     pub winning_team: String,
+    pub winning_margin: u32,
     pub win_by_runs: bool,
     pub win_by_innings: bool,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CricbuzzMatchHeaderPlayer {
+    pub id: u32,
+    pub name: String,
+    pub full_name: String,
+    pub team_name: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMatchHeaderRevisedTarget {
     pub reason: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMatchHeaderMatchTeamInfo {
     pub batting_team_id: u32,
     pub batting_team_short_name: String,
@@ -112,8 +202,8 @@ pub struct CricbuzzMatchHeaderMatchTeamInfo {
     pub bowling_team_short_name: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMatchHeaderTeam {
     pub id: u32,
     pub name: String,
@@ -121,8 +211,8 @@ pub struct CricbuzzMatchHeaderTeam {
     pub short_name: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMatchHeader {
     pub match_id: u32,
     pub match_description: String,
@@ -140,7 +230,7 @@ pub struct CricbuzzMatchHeader {
     pub toss_results: CricbuzzMatchHeaderTossResults,
     pub result: Option<CricbuzzMatchHeaderResults>,
     pub revised_target: Option<CricbuzzMatchHeaderRevisedTarget>,
-    // pub players_of_the_match: Vec<String>,
+    pub players_of_the_match: Vec<CricbuzzMatchHeaderPlayer>,
     // pub players_of_the_series: Vec<String>,
     pub match_team_info: Vec<CricbuzzMatchHeaderMatchTeamInfo>,
     pub is_match_not_covered: bool,
@@ -151,8 +241,8 @@ pub struct CricbuzzMatchHeader {
     pub series_name: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMiniscoreBatsman {
     pub bat_balls: u32,
     pub bat_dots: u32,
@@ -165,16 +255,16 @@ pub struct CricbuzzMiniscoreBatsman {
     pub bat_runs: u32,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMiniscoreBatTeam {
     pub team_id: u32,
     pub team_score: u32,
     pub team_wkts: u32,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMiniscoreBowler {
     pub bowl_id: u32,
     pub bowl_name: String,
@@ -187,14 +277,15 @@ pub struct CricbuzzMiniscoreBowler {
     pub bowl_econ: f32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct CricbuzzMiniscorePartnership {
     pub balls: u32,
     pub runs: u32,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMiniscoreMatchScoreDetailsInningsScore {
     pub innings_id: u32,
     pub bat_team_id: u32,
@@ -206,16 +297,16 @@ pub struct CricbuzzMiniscoreMatchScoreDetailsInningsScore {
     pub is_follow_on: bool,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMiniscoreMatchScoreDetailsTossResults {
     pub toss_winner_id: u32,
     pub toss_winner_name: String,
     pub decision: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMiniscoreMatchScoreDetailsMatchTeamInfo {
     pub batting_team_id: u32,
     pub batting_team_short_name: String,
@@ -223,8 +314,8 @@ pub struct CricbuzzMiniscoreMatchScoreDetailsMatchTeamInfo {
     pub bowling_team_short_name: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMiniscoreMatchScoreDetails {
     pub match_id: u32,
     pub innings_score_list: Vec<CricbuzzMiniscoreMatchScoreDetailsInningsScore>,
@@ -237,13 +328,25 @@ pub struct CricbuzzMiniscoreMatchScoreDetails {
     pub highlighted_team_id: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct CricbuzzMiniscoreLatestPerformance {
     pub runs: u32,
     pub wkts: u32,
     pub label: String,
 }
 
+/// A powerplay of the current innings. The overs are given as "0.1" to "10.0".
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CricbuzzPowerplay {
+    pub pp_id: u32,
+    pub pp_overs_from: f32,
+    pub pp_overs_to: f32,
+    pub pp_type: String,
+    pub runs_scored: u32,
+}
+
 /* -- Not Used -- */
 // #[derive(Debug, Deserialize)]
 // #[serde(rename_all = "camelCase")]
@@ -261,8 +364,8 @@ pub struct CricbuzzMiniscoreLatestPerformance {
 //     pub team2_unsuccessful: u32,
 // }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzMiniscore {
     pub innings_id: u32,
     pub batsman_striker: CricbuzzMiniscoreBatsman,
@@ -275,26 +378,83 @@ pub struct CricbuzzMiniscore {
     pub partner_ship: CricbuzzMiniscorePartnership,
     pub current_run_rate: f32,
     pub required_run_rate: f32,
+    /// Runs the team batting last has to score to win, 0 before the last innings
+    pub target: u32,
     pub last_wicket: Option<String>,
     pub match_score_details: CricbuzzMiniscoreMatchScoreDetails,
     pub latest_performance: Vec<CricbuzzMiniscoreLatestPerformance>,
+    /// Powerplays of the current innings keyed as "pp_1", "pp_2" and so on
+    pub pp_data: HashMap<String, CricbuzzPowerplay>,
     // pub match_udrs: CricbuzzMiniscoreMatchUdrs,
     pub overs_rem: Option<f32>,
+    pub over_summary_list: Vec<CricbuzzOverSeparator>,
     pub status: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct CricbuzzJson {
-    /* Unused Field */
-    // pub commentary_list: Vec<CricbuzzCommentary>,
+    pub commentary_list: Vec<CricbuzzCommentary>,
     pub match_header: CricbuzzMatchHeader,
     pub miniscore: CricbuzzMiniscore,
     pub page: String,
     pub enable_no_content: bool,
+    /// Expected fields which were missing from the response and have been defaulted
+    #[serde(skip)]
+    pub missing_fields: Vec<String>,
+}
+
+/// Fields the app relies on, as JSON pointers. Cricbuzz leaves some of them out before the
+/// toss, during innings breaks and for matches it does not cover.
+const EXPECTED_FIELDS: [&str; 15] = [
+    "/matchHeader",
+    "/matchHeader/matchTeamInfo",
+    "/matchHeader/team1",
+    "/matchHeader/team2",
+    "/matchHeader/tossResults",
+    "/miniscore",
+    "/miniscore/batsmanStriker",
+    "/miniscore/batsmanNonStriker",
+    "/miniscore/bowlerStriker",
+    "/miniscore/bowlerNonStriker",
+    "/miniscore/partnerShip",
+    "/miniscore/latestPerformance",
+    "/miniscore/matchScoreDetails",
+    "/miniscore/matchScoreDetails/inningsScoreList",
+    "/miniscore/matchScoreDetails/matchTeamInfo",
+];
+
+/// Returns the expected fields missing from a response, in dotted form. Eg.
+/// "miniscore.batsmanStriker". Fields whose parent is missing are not listed.
+fn missing_fields(value: &serde_json::Value) -> Vec<String> {
+    let mut missing: Vec<&str> = vec![];
+    for field in EXPECTED_FIELDS {
+        let parent_missing = missing
+            .iter()
+            .any(|m| field.starts_with(m) && field[m.len()..].starts_with('/'));
+        if !parent_missing && value.pointer(field).is_none() {
+            missing.push(field);
+        }
+    }
+
+    missing
+        .iter()
+        .map(|m| m.trim_start_matches('/').replace('/', "."))
+        .collect()
 }
 
 impl CricbuzzJson {
+    /// Parses a commentary response, defaulting every field that is missing from it.
+    /// The expected fields that were missing are noted in `missing_fields`.
+    pub fn from_json_str(json: &str) -> Result<CricbuzzJson, serde_json::Error> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let missing_fields = missing_fields(&value);
+
+        let mut res: CricbuzzJson = serde_json::from_value(value)?;
+        res.missing_fields = missing_fields;
+        Ok(res)
+    }
+
     pub fn bat_striker_name(&self) -> &str {
         self.miniscore.batsman_striker.bat_name.as_str()
     }
@@ -423,6 +583,101 @@ impl CricbuzzJson {
         &self.miniscore.match_score_details.match_format
     }
 
+    pub fn commentary_list(&self) -> &[CricbuzzCommentary] {
+        &self.commentary_list
+    }
+
+    /// Returns the summaries of all the overs present in the response
+    pub fn over_summaries(&self) -> Vec<&CricbuzzOverSeparator> {
+        self.miniscore
+            .over_summary_list
+            .iter()
+            .chain(
+                self.commentary_list
+                    .iter()
+                    .filter_map(|c| c.over_separator.as_ref()),
+            )
+            .collect()
+    }
+
+    /// Returns whether an over of an innings, starting from 1, is part of a powerplay.
+    /// Only the powerplays of the current innings are known.
+    pub fn is_powerplay_over(&self, innings_id: u32, over: u32) -> bool {
+        innings_id == self.miniscore.innings_id
+            && self.miniscore.pp_data.values().any(|pp| {
+                // "0.1" to "10.0" covers the 1st to the 10th over
+                let first = pp.pp_overs_from as u32 + 1;
+                let last = pp.pp_overs_to.ceil() as u32;
+                (first..=last).contains(&over)
+            })
+    }
+
+    /// Returns the number of overs in an innings of a limited overs match
+    pub fn format_total_overs(&self) -> Option<u32> {
+        match self.match_format() {
+            "ODI" => Some(50),
+            "T20" => Some(20),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of days the match is scheduled to last. Eg. 4 or 5 for a Test.
+    /// Until the match finishes, Cricbuzz sends the start of its last day as its completion
+    /// time. `None` if that is not known, as for timeless matches.
+    pub fn scheduled_days(&self) -> Option<u32> {
+        const DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+        let header = &self.match_header;
+        // The first day starts with play and the last one at midnight, which is before
+        // play on the only day of a one-day match
+        let last_day_end = header.match_complete_timestamp.saturating_add(DAY_MILLIS);
+        let days = last_day_end
+            .checked_sub(header.match_start_timestamp)
+            .filter(|&millis| millis > 0)?
+            .div_ceil(DAY_MILLIS);
+        u32::try_from(days).ok()
+    }
+
+    /// Returns whether the match has finished
+    pub fn is_complete(&self) -> bool {
+        self.match_header.complete || self.match_header.state == "Complete"
+    }
+
+    /// Returns the result of a finished match. Eg. "Bangladesh won by 33 runs".
+    /// Falls back to the match status for ties, draws and abandoned matches.
+    pub fn result_summary(&self) -> String {
+        let result = match &self.match_header.result {
+            Some(result) if !result.winning_team.is_empty() && result.winning_margin > 0 => result,
+            _ => return self.match_header.status.clone(),
+        };
+
+        let plural = |n: u32, unit: &str| {
+            if n == 1 {
+                format!("{} {}", n, unit)
+            } else {
+                format!("{} {}s", n, unit)
+            }
+        };
+        let margin = if result.win_by_innings {
+            format!("an innings and {}", plural(result.winning_margin, "run"))
+        } else if result.win_by_runs {
+            plural(result.winning_margin, "run")
+        } else {
+            plural(result.winning_margin, "wkt")
+        };
+
+        format!("{} won by {}", result.winning_team, margin)
+    }
+
+    /// Returns the names of the players of the match along with their team
+    pub fn players_of_the_match(&self) -> Vec<String> {
+        self.match_header
+            .players_of_the_match
+            .iter()
+            .map(|p| format!("{} ({})", p.name, p.team_name))
+            .collect()
+    }
+
     pub fn home_team_name(&self) -> &str {
         &self.match_header.team1.short_name
     }
@@ -431,3 +686,164 @@ impl CricbuzzJson {
         &self.match_header.team2.short_name
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cricbuzz_api::{CricbuzzCommentary, CricbuzzCommentaryFormat, CricbuzzCommentaryFormats},
+        test_utils::load_json,
+    };
+
+    fn commentary(text: &str, ids: &[&str], values: &[&str]) -> CricbuzzCommentary {
+        CricbuzzCommentary {
+            comm_text: text.to_string(),
+            commentary_formats: CricbuzzCommentaryFormats {
+                bold: Some(CricbuzzCommentaryFormat {
+                    format_id: ids.iter().map(|id| id.to_string()).collect(),
+                    format_value: values.iter().map(|v| v.to_string()).collect(),
+                }),
+            },
+            ..CricbuzzCommentary::default()
+        }
+    }
+
+    #[test]
+    fn test_comm_text_segments() {
+        let comm = commentary(
+            " Taskin Ahmed to Pathum Nissanka, B0$, pulled over midwicket ",
+            &["B0$"],
+            &["FOUR"],
+        );
+        assert_eq!(
+            comm.comm_text_segments(),
+            vec![
+                ("Taskin Ahmed to Pathum Nissanka, ".to_string(), false),
+                ("FOUR".to_string(), true),
+                (", pulled over midwicket".to_string(), false),
+            ]
+        );
+
+        // Placeholders are substituted in the order they appear, not the order listed
+        let comm = commentary("B1$ B0$", &["B0$", "B1$"], &["Wicket", "Drinks"]);
+        assert_eq!(
+            comm.comm_text_segments(),
+            vec![
+                ("Drinks".to_string(), true),
+                (" ".to_string(), false),
+                ("Wicket".to_string(), true),
+            ]
+        );
+
+        // Malformed entries with an empty placeholder are left as they are
+        let comm = commentary("B0$ and over", &["", "B0$"], &["Drinks", "Wicket"]);
+        assert_eq!(
+            comm.comm_text_segments(),
+            vec![
+                ("Wicket".to_string(), true),
+                (" and over".to_string(), false),
+            ]
+        );
+
+        let plain = CricbuzzCommentary {
+            comm_text: " Dot ball ".to_string(),
+            ..CricbuzzCommentary::default()
+        };
+        assert_eq!(
+            plain.comm_text_segments(),
+            vec![("Dot ball".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_commentary_events() {
+        let comm = CricbuzzCommentary {
+            event: "FOUR,HIGHSCORE".to_string(),
+            ..CricbuzzCommentary::default()
+        };
+        assert!(comm.is_four());
+        assert!(!comm.is_six());
+        assert!(!comm.is_wicket());
+
+        let comm = CricbuzzCommentary {
+            event: "WICKET".to_string(),
+            ..CricbuzzCommentary::default()
+        };
+        assert!(comm.is_wicket());
+    }
+
+    #[test]
+    fn test_parse_complete_response() {
+        let json = load_json("cricbuzz_odi_first_innings.json");
+        assert!(json.missing_fields.is_empty());
+    }
+
+    #[test]
+    fn test_parse_pre_toss_response() {
+        let json = load_json("cricbuzz_odi_pre_toss.json");
+
+        assert_eq!(
+            json.missing_fields,
+            vec![
+                "matchHeader.matchTeamInfo",
+                "matchHeader.tossResults",
+                "miniscore"
+            ]
+        );
+        assert_eq!(json.home_team_name(), "BAN");
+        assert_eq!(json.commentary_list().len(), 2);
+        assert!(json
+            .miniscore
+            .match_score_details
+            .innings_score_list
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_innings_break_response() {
+        let json = load_json("cricbuzz_odi_innings_break.json");
+
+        assert_eq!(
+            json.missing_fields,
+            vec![
+                "matchHeader.matchTeamInfo",
+                "miniscore.batsmanStriker",
+                "miniscore.batsmanNonStriker",
+                "miniscore.bowlerStriker",
+                "miniscore.bowlerNonStriker",
+                "miniscore.partnerShip",
+                "miniscore.latestPerformance"
+            ]
+        );
+        assert_eq!(json.bat_striker_name(), "");
+        assert_eq!(json.match_format(), "ODI");
+    }
+
+    #[test]
+    fn test_result_summary() {
+        let mut json = load_json("cricbuzz_odi_complete.json");
+        assert!(json.is_complete());
+        assert_eq!(json.result_summary(), "Bangladesh won by 33 runs");
+        assert_eq!(json.players_of_the_match(), vec!["Mushfiqur Rahim (BAN)"]);
+
+        let result = json.match_header.result.as_mut().unwrap();
+        result.win_by_runs = false;
+        result.winning_margin = 1;
+        assert_eq!(json.result_summary(), "Bangladesh won by 1 wkt");
+
+        let json = load_json("cricbuzz_odi_second_innings.json");
+        assert!(!json.is_complete());
+        assert_eq!(json.result_summary(), "Bangladesh opt to bat");
+    }
+
+    #[test]
+    fn test_scheduled_days() {
+        assert_eq!(
+            load_json("cricbuzz_test_first_innings.json").scheduled_days(),
+            Some(5)
+        );
+        assert_eq!(
+            load_json("cricbuzz_odi_first_innings.json").scheduled_days(),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! # Diff
+//!
+//! Finds what changed in a match between two consecutive snapshots of its details. The
+//! changes are pushed to the clients of the server, so they do not have to poll it.
+
+use serde::Serialize;
+
+use crate::cricbuzz_api::{CricbuzzJson, CricbuzzMiniscoreBatsman};
+
+/// Score of the innings being played
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreDiff {
+    pub innings_id: u32,
+    /// Short name of the batting team
+    pub team: String,
+    pub runs: u32,
+    pub wickets: u32,
+    pub overs: f32,
+}
+
+/// A batsman at the crease
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatsmanDiff {
+    pub id: u32,
+    pub name: String,
+    pub runs: u32,
+    pub balls: u32,
+    pub on_strike: bool,
+}
+
+/// What changed in a match since it was last fetched. Only the fields which changed are
+/// set, holding their latest values.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchDiff {
+    pub match_id: u32,
+    /// Short form of the teams playing the match. Eg. BAN vs SL
+    pub match_name: String,
+    /// Set when the runs, wickets or overs of the innings being played changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<ScoreDiff>,
+    /// Set when a wicket fell. Eg. "Liton Das c Kusal Perera b Chameera 43(48)"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wicket: Option<String>,
+    /// Set when the batsmen at the crease or their scores changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batsmen: Option<Vec<BatsmanDiff>>,
+    /// Set when the status of the match changed. Eg. "SL need 212 runs"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+/// Returns what changed in a match between two snapshots of its details, `None` if
+/// nothing worth pushing changed
+///
+/// # Arguments
+///
+/// * `match_id` - Number used by the provider to identify the match
+/// * `match_name` - Short form of the teams playing the match
+/// * `prev` - Details of the match fetched before `curr`
+/// * `curr` - Latest details of the match
+pub fn diff_match(
+    match_id: u32,
+    match_name: &str,
+    prev: &CricbuzzJson,
+    curr: &CricbuzzJson,
+) -> Option<MatchDiff> {
+    let curr_score = current_score(curr);
+    let prev_score = current_score(prev);
+    let score = if curr_score != prev_score {
+        curr_score
+    } else {
+        None
+    };
+
+    let wicket = match (&prev_score, &score) {
+        (Some(prev_score), Some(score))
+            if score.innings_id == prev_score.innings_id && score.wickets > prev_score.wickets =>
+        {
+            curr.miniscore.last_wicket.clone()
+        }
+        _ => None,
+    };
+
+    let curr_batsmen = batsmen_at_crease(curr);
+    let batsmen = if curr_batsmen != batsmen_at_crease(prev) {
+        Some(curr_batsmen)
+    } else {
+        None
+    };
+
+    let curr_status = &curr.miniscore.match_score_details.custom_status;
+    let status = if *curr_status != prev.miniscore.match_score_details.custom_status {
+        Some(curr_status.clone())
+    } else {
+        None
+    };
+
+    if score.is_none() && batsmen.is_none() && status.is_none() {
+        return None;
+    }
+
+    Some(MatchDiff {
+        match_id,
+        match_name: match_name.to_string(),
+        score,
+        wicket,
+        batsmen,
+        status,
+    })
+}
+
+/// Returns the score of the innings being played, if it has started
+fn current_score(json: &CricbuzzJson) -> Option<ScoreDiff> {
+    json.miniscore
+        .match_score_details
+        .innings_score_list
+        .iter()
+        .find(|inns| inns.innings_id == json.miniscore.innings_id)
+        .map(|inns| ScoreDiff {
+            innings_id: inns.innings_id,
+            team: inns.bat_team_name.clone(),
+            runs: inns.score,
+            wickets: inns.wickets,
+            overs: inns.overs,
+        })
+}
+
+/// Returns the batsmen at the crease, leaving out the ones Cricbuzz has not filled in
+fn batsmen_at_crease(json: &CricbuzzJson) -> Vec<BatsmanDiff> {
+    let to_diff = |bat: &CricbuzzMiniscoreBatsman, on_strike: bool| BatsmanDiff {
+        id: bat.bat_id,
+        name: bat.bat_name.clone(),
+        runs: bat.bat_runs,
+        balls: bat.bat_balls,
+        on_strike,
+    };
+
+    [
+        to_diff(&json.miniscore.batsman_striker, true),
+        to_diff(&json.miniscore.batsman_non_striker, false),
+    ]
+    .iter()
+    .filter(|bat| bat.id != 0)
+    .cloned()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diff::diff_match, test_utils::load_json};
+
+    #[test]
+    fn test_diff_match() {
+        let prev = load_json("cricbuzz_odi_second_innings.json");
+        assert_eq!(diff_match(36096, "BAN vs SL", &prev, &prev), None);
+
+        let mut curr = load_json("cricbuzz_odi_second_innings.json");
+        curr.miniscore.match_score_details.custom_status = "SL need 200 runs".to_string();
+        let diff = diff_match(36096, "BAN vs SL", &prev, &curr).unwrap();
+        assert_eq!(diff.score, None);
+        assert_eq!(diff.batsmen, None);
+        assert_eq!(diff.status.as_deref(), Some("SL need 200 runs"));
+
+        let inns = &mut curr.miniscore.match_score_details.innings_score_list[0];
+        inns.score += 4;
+        inns.wickets += 1;
+        curr.miniscore.batsman_striker.bat_runs += 4;
+        curr.miniscore.batsman_striker.bat_balls += 1;
+
+        let diff = diff_match(36096, "BAN vs SL", &prev, &curr).unwrap();
+        let score = diff.score.unwrap();
+        assert_eq!(
+            (score.team.as_str(), score.runs, score.wickets),
+            ("SL", 45, 2)
+        );
+        assert_eq!(diff.wicket, curr.miniscore.last_wicket);
+        let batsmen = diff.batsmen.unwrap();
+        assert_eq!(batsmen.len(), 2);
+        assert!(batsmen[0].on_strike);
+        assert_eq!(batsmen[0].runs, curr.miniscore.batsman_striker.bat_runs);
+    }
+}
@@ -1 +1,3 @@
+pub mod output;
+pub mod terminal;
 pub mod ui;
@@ -0,0 +1,228 @@
+//! # Output
+//!
+//! Non-interactive output of the live matches, meant to be piped into status bars,
+//! scripts and cron jobs.
+
+use serde::Serialize;
+use tui::text::Spans;
+
+use crate::{app::App, display::ui::get_match_score_lines};
+
+/// Format in which the live matches are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Plain,
+    Json,
+}
+
+impl OutputFormat {
+    /// Returns the format corresponding to its command line name
+    pub fn from_name(name: &str) -> Option<OutputFormat> {
+        match name {
+            "plain" => Some(OutputFormat::Plain),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BatsmanOutput<'a> {
+    name: &'a str,
+    on_strike: bool,
+    runs: u32,
+    balls: u32,
+    fours: u32,
+    sixes: u32,
+    strike_rate: f32,
+}
+
+#[derive(Serialize)]
+struct BowlerOutput<'a> {
+    name: &'a str,
+    on_strike: bool,
+    overs: f32,
+    maidens: u32,
+    runs: u32,
+    wickets: u32,
+    economy: f32,
+}
+
+#[derive(Serialize)]
+struct MatchOutput<'a> {
+    match_id: u32,
+    name: &'a str,
+    format: &'a str,
+    scores: Vec<String>,
+    status: &'a str,
+    batsmen: Vec<BatsmanOutput<'a>>,
+    bowlers: Vec<BowlerOutput<'a>>,
+}
+
+/// Returns all the live matches of the app formatted for printing to stdout
+pub fn format_matches(app: &App, format: OutputFormat) -> String {
+    let matches: Vec<MatchOutput> = (0..app.matches_info.len())
+        .map(|idx| build_match_output(app, idx))
+        .collect();
+
+    match format {
+        OutputFormat::Plain => {
+            if matches.is_empty() {
+                return "No live matches :(".to_string();
+            }
+
+            matches
+                .iter()
+                .map(format_plain_match)
+                .collect::<Vec<String>>()
+                .join("\n\n")
+        }
+        // Serialising plain structs to JSON will not fail
+        OutputFormat::Json => serde_json::to_string_pretty(&matches).unwrap_or_default(),
+    }
+}
+
+fn build_match_output(app: &App, idx: usize) -> MatchOutput<'_> {
+    let mi = &app.matches_info[idx];
+    let json = &mi.cricbuzz_info;
+
+    MatchOutput {
+        match_id: mi.cricbuzz_match_id,
+        name: &mi.match_short_name,
+        format: json.match_format(),
+        scores: get_match_score_lines(app, idx)
+            .iter()
+            .map(spans_to_string)
+            .collect(),
+        status: &json.miniscore.match_score_details.custom_status,
+        batsmen: vec![
+            BatsmanOutput {
+                name: json.bat_striker_name(),
+                on_strike: true,
+                runs: json.bat_striker_runs(),
+                balls: json.bat_striker_balls(),
+                fours: json.bat_striker_fours(),
+                sixes: json.bat_striker_sixes(),
+                strike_rate: json.bat_striker_strike_rate(),
+            },
+            BatsmanOutput {
+                name: json.bat_non_striker_name(),
+                on_strike: false,
+                runs: json.bat_non_striker_runs(),
+                balls: json.bat_non_striker_balls(),
+                fours: json.bat_non_striker_fours(),
+                sixes: json.bat_non_striker_sixes(),
+                strike_rate: json.bat_non_striker_strike_rate(),
+            },
+        ],
+        bowlers: vec![
+            BowlerOutput {
+                name: json.bowl_striker_name(),
+                on_strike: true,
+                overs: json.bowl_striker_ovs(),
+                maidens: json.bowl_striker_maidens(),
+                runs: json.bowl_striker_runs(),
+                wickets: json.bowl_striker_wkts(),
+                economy: json.bowl_striker_econ(),
+            },
+            BowlerOutput {
+                name: json.bowl_non_striker_name(),
+                on_strike: false,
+                overs: json.bowl_non_striker_ovs(),
+                maidens: json.bowl_non_striker_maidens(),
+                runs: json.bowl_non_striker_runs(),
+                wickets: json.bowl_non_striker_wkts(),
+                economy: json.bowl_non_striker_econ(),
+            },
+        ],
+    }
+}
+
+fn format_plain_match(m: &MatchOutput) -> String {
+    let mut lines = vec![format!("{} - {}", m.name, m.format)];
+    lines.extend(m.scores.iter().cloned());
+    lines.push(m.status.to_string());
+
+    for b in &m.batsmen {
+        lines.push(format!(
+            "{}{} {}({}) 4s: {} 6s: {} SR: {}",
+            b.name,
+            if b.on_strike { " *" } else { "" },
+            b.runs,
+            b.balls,
+            b.fours,
+            b.sixes,
+            b.strike_rate
+        ));
+    }
+
+    for b in &m.bowlers {
+        lines.push(format!(
+            "{}{} {}-{}-{}-{} ECO: {}",
+            b.name,
+            if b.on_strike { " *" } else { "" },
+            b.overs,
+            b.maidens,
+            b.runs,
+            b.wickets,
+            b.economy
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// Concatenates the text of all the spans in a line, dropping the styles
+fn spans_to_string(spans: &Spans) -> String {
+    spans
+        .0
+        .iter()
+        .map(|s| s.content.as_ref())
+        .collect::<Vec<&str>>()
+        .concat()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        app::App,
+        display::output::{format_matches, OutputFormat},
+        test_utils::load_match,
+    };
+
+    // Path is relative to where the testfile is present
+    const SNAPSHOTS_PATH: &str = "../../tests/snapshots";
+
+    fn get_app() -> App {
+        let mut app = App::default();
+
+        app.matches_info
+            .push(load_match("cricbuzz_odi_second_innings.json", None));
+
+        app
+    }
+
+    #[test]
+    fn test_format_matches_plain() {
+        let out = format_matches(&get_app(), OutputFormat::Plain);
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_path(SNAPSHOTS_PATH);
+
+        settings.bind(|| {
+            insta::assert_display_snapshot!(out);
+        });
+    }
+
+    #[test]
+    fn test_format_matches_json() {
+        let out = format_matches(&get_app(), OutputFormat::Json);
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_path(SNAPSHOTS_PATH);
+
+        settings.bind(|| {
+            insta::assert_display_snapshot!(out);
+        });
+    }
+}
@@ -0,0 +1,238 @@
+//! # Terminal
+//!
+//! The interactive terminal UI. Draws the matches of an `App`, handles the key presses
+//! and refreshes the matches in the background every tick.
+
+use std::{
+    future::Future,
+    io::{self, Write},
+    sync::mpsc,
+};
+
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use tui::{backend::CrosstermBackend, Terminal};
+
+use crate::{
+    alert,
+    app::{fetch_browser, fetch_opened_match, fetch_update, App, Fetched},
+    config::{AlertConfig, Config},
+    display::ui::{draw_ui, UiState},
+    event::{self, Action, KeyBindings},
+};
+
+/// Lines scrolled by a page up or page down
+const PAGE_LINES: u16 = 10;
+
+/// Runs the UI until the user quits or there are no matches left to show. When no match is
+/// live, the match browser is opened so an upcoming or recent match can be picked instead.
+/// Must be called from within a Tokio runtime, as the matches are fetched in background
+/// tasks.
+///
+/// # Arguments
+///
+/// * `app` - The matches to show
+/// * `tick_rate` - Milliseconds between refreshes of the matches
+/// * `config` - Colours, layout, key bindings and alerts of the UI
+pub fn run(mut app: App, tick_rate: u64, config: Config) -> Result<(), Box<dyn std::error::Error>> {
+    let mut ui_state = UiState::with_config(app.matches_info.len(), config.theme, config.layout);
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    enable_raw_mode()?;
+
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let events = event::Events::new(tick_rate);
+    let (update_tx, update_rx) = mpsc::channel();
+    let key_bindings = KeyBindings::new(&config.keys);
+
+    if app.matches_info.is_empty() {
+        ui_state.toggle_browser();
+        start_browser(&mut app, &update_tx, &events);
+    }
+
+    loop {
+        // The browser is kept open without any matches, unless it has nothing to list either
+        let browsing = ui_state.show_browser && !matches!(&app.browser, Some(b) if b.is_empty());
+        if !app.matches_info.is_empty() || browsing {
+            terminal.draw(|f| {
+                draw_ui(f, &app, &mut ui_state);
+            })?;
+        } else {
+            safely_close_tui()?;
+            println!("No live matches :(");
+            if let Some(err) = &app.last_error {
+                eprintln!("{}", err.error);
+            }
+            break;
+        }
+
+        match events.next()? {
+            event::Event::Input(key) => {
+                let num_browser_entries = app.browser.as_ref().map_or(0, Vec::len);
+                match key_bindings.action(key) {
+                    Some(Action::Quit) => {
+                        safely_close_tui()?;
+                        break;
+                    }
+                    Some(Action::NextTab) => {
+                        ui_state.add_focused_tab(1);
+                    }
+                    Some(Action::PrevTab) => {
+                        ui_state.sub_focused_tab(1);
+                    }
+                    Some(Action::CloseTab) => {
+                        app.close_match(ui_state.focused_tab);
+                        ui_state.update_on_tick(&[ui_state.focused_tab], app.matches_info.len());
+                    }
+                    Some(Action::ToggleBrowser) => {
+                        ui_state.toggle_browser();
+                        if ui_state.show_browser {
+                            start_browser(&mut app, &update_tx, &events);
+                        }
+                    }
+                    Some(Action::Open) if ui_state.show_browser => {
+                        let entry = app
+                            .browser
+                            .as_ref()
+                            .and_then(|entries| entries.get(ui_state.browser_selected))
+                            .cloned();
+                        if let Some(entry) = entry {
+                            // Without any other match the browser stays open until the
+                            // match has been fetched
+                            if !app.matches_info.is_empty() {
+                                ui_state.toggle_browser();
+                            }
+                            let provider = app.provider();
+                            let permits = app.request_permits();
+                            spawn_fetch(&update_tx, &events, async move {
+                                Fetched::Opened(Box::new(
+                                    fetch_opened_match(provider.as_ref(), &permits, &entry).await,
+                                ))
+                            });
+                        }
+                    }
+                    Some(Action::ScrollDown) => ui_state.scroll_down(1, num_browser_entries),
+                    Some(Action::ScrollUp) => ui_state.scroll_up(1),
+                    Some(Action::PageDown) => ui_state.scroll_down(PAGE_LINES, num_browser_entries),
+                    Some(Action::PageUp) => ui_state.scroll_up(PAGE_LINES),
+                    Some(Action::JumpToTop) => ui_state.scroll_to_top(),
+                    Some(Action::JumpToBottom) => ui_state.scroll_to_bottom(num_browser_entries),
+                    Some(Action::Refresh) => start_refresh(&mut app, &update_tx, &events),
+                    Some(Action::TogglePane) => {
+                        ui_state.toggle_focused_pane();
+                    }
+                    Some(Action::NextView) => {
+                        ui_state.next_match_view();
+                    }
+                    Some(Action::NextInnings) => {
+                        if let Some(mi) = app.matches_info.get(ui_state.focused_tab) {
+                            let num_innings = mi.innings_over_history().len();
+                            ui_state.next_manhattan_innings(num_innings);
+                        }
+                    }
+                    Some(Action::Open) | None => {}
+                };
+            }
+
+            event::Event::Tick => start_refresh(&mut app, &update_tx, &events),
+
+            event::Event::Refreshed => {
+                while let Ok(fetched) = update_rx.try_recv() {
+                    match fetched {
+                        Fetched::Update(update) => {
+                            let invalid_idx = app.apply_update(update);
+                            ui_state.update_on_tick(&invalid_idx, app.matches_info.len());
+                            app.refreshing = false;
+                            notify(&config.alerts, &app.take_alerts(), &mut ui_state)?;
+                        }
+                        Fetched::Browser(entries) => app.set_browser(entries),
+                        Fetched::Opened(opened) => {
+                            if let Some(idx) = app.open_match(*opened) {
+                                ui_state.focus_tab(idx);
+                                if ui_state.show_browser && app.matches_info.len() == 1 {
+                                    ui_state.toggle_browser();
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Refreshes the details of all the matches in the background, so key presses are handled
+/// while requests are in flight. Does nothing if the previous refresh has not finished yet.
+fn start_refresh(app: &mut App, update_tx: &mpsc::Sender<Fetched>, events: &event::Events) {
+    if !app.refreshing {
+        app.refreshing = true;
+        let provider = app.provider();
+        let permits = app.request_permits();
+        let match_ids = app.refresh_match_ids();
+        let known_ids = app.known_match_ids();
+        spawn_fetch(update_tx, events, async move {
+            Fetched::Update(fetch_update(provider.as_ref(), &permits, match_ids, known_ids).await)
+        });
+    }
+}
+
+/// Lists the upcoming and recently completed matches in the background. Always lists them
+/// afresh, as matches finish and start all the time.
+fn start_browser(app: &mut App, update_tx: &mpsc::Sender<Fetched>, events: &event::Events) {
+    app.browser = None;
+    let provider = app.provider();
+    let permits = app.request_permits();
+    let filter = app.filter.clone();
+    spawn_fetch(update_tx, events, async move {
+        Fetched::Browser(fetch_browser(provider.as_ref(), &permits, &filter).await)
+    });
+}
+
+/// Acts upon everything that happened in the matches, as configured in the `[alerts]`
+/// table of the config file
+fn notify(config: &AlertConfig, alerts: &[alert::Alert], ui_state: &mut UiState) -> io::Result<()> {
+    let mut ring_bell = false;
+
+    for alert in alerts.iter().filter(|a| config.is_enabled(a.kind)) {
+        ring_bell |= config.bell;
+        if config.flash {
+            ui_state.flash(alert.match_id);
+        }
+        if let Some(command) = &config.command {
+            alert::run_hook(command, alert);
+        }
+    }
+
+    if ring_bell {
+        let mut stdout = io::stdout();
+        stdout.write_all(b"\x07")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// Runs a request in a background task and wakes up the UI loop once it is done
+fn spawn_fetch<F>(update_tx: &mpsc::Sender<Fetched>, events: &event::Events, fut: F)
+where
+    F: Future<Output = Fetched> + Send + 'static,
+{
+    let update_tx = update_tx.clone();
+    let event_tx = events.sender();
+    tokio::spawn(async move {
+        if update_tx.send(fut.await).is_ok() {
+            let _ = event_tx.send(event::Event::Refreshed);
+        }
+    });
+}
+
+fn safely_close_tui() -> Result<(), Box<dyn std::error::Error>> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    Ok(())
+}
@@ -1,24 +1,75 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    time::SystemTime,
+};
+
+use chrono::{Local, TimeZone};
 
 use crate::{
-    app::{App, MatchInningsInfo},
-    cricbuzz_api::CricbuzzMiniscoreMatchScoreDetailsInningsScore,
+    app::{App, BrowserStatus, Chase, MatchInningsInfo, ProjectedScore},
+    config::{PaneLayout, Theme},
+    cricbuzz_api::{
+        CricbuzzCommentary, CricbuzzMiniscoreMatchScoreDetails,
+        CricbuzzMiniscoreMatchScoreDetailsInningsScore,
+    },
 };
 use tui::{
     backend::Backend,
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols,
     text::{Span, Spans},
-    widgets::{Block, Borders, Paragraph, Row, Table, Tabs, Wrap},
+    widgets::{
+        Axis, BarChart, Block, Borders, Chart, Dataset, GraphType, List, ListItem, ListState,
+        Paragraph, Row, Table, Tabs, Widget, Wrap,
+    },
     Frame,
 };
 
-/// Stores the UI state i.e. current tab, scroll state of scorecard
+/// The scrollable panes of a match tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Scorecard,
+    Commentary,
+}
+
+/// What is shown below the commentary of a match tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchView {
+    Scorecard,
+    Worm,
+    Manhattan,
+}
+
+/// Colours of the innings in the charts, in the order the innings were played
+const INNINGS_COLORS: [Color; 4] = [Color::Cyan, Color::Yellow, Color::Magenta, Color::Blue];
+
+/// Stores the UI state i.e. current tab, scroll state of scorecard and commentary
 pub struct UiState {
     /// Selected tab
     pub focused_tab: usize,
+    /// Pane which receives the scroll key presses
+    pub focused_pane: Pane,
+    /// View shown below the commentary
+    pub match_view: MatchView,
+    /// Innings shown in the Manhattan chart, the latest innings if not selected
+    pub manhattan_innings: Option<usize>,
     /// Stores current scroll value and max scroll value for each tab
     pub scrd_scroll: Vec<(u16, u16)>,
+    /// Stores current commentary scroll value and max scroll value for each tab
+    pub comm_scroll: Vec<(u16, u16)>,
+    /// Whether the upcoming and recent matches are shown instead of the focused tab
+    pub show_browser: bool,
+    /// Selected match in the match browser
+    pub browser_selected: usize,
+    /// Colours used to draw the UI
+    pub theme: Theme,
+    /// Sizes and contents of the panes of a match tab
+    pub layout: PaneLayout,
+    /// Matches whose tab titles flash until they are selected, as something happened in them
+    pub flashing: HashSet<u32>,
 }
 
 impl UiState {
@@ -30,10 +81,62 @@ impl UiState {
     pub fn new(num_tabs: usize) -> UiState {
         UiState {
             focused_tab: 0,
+            focused_pane: Pane::Scorecard,
+            match_view: MatchView::Scorecard,
+            manhattan_innings: None,
             scrd_scroll: vec![(0, 0); num_tabs],
+            comm_scroll: vec![(0, 0); num_tabs],
+            show_browser: false,
+            browser_selected: 0,
+            theme: Theme::default(),
+            layout: PaneLayout::default(),
+            flashing: HashSet::new(),
+        }
+    }
+
+    /// Return a new `UiState` struct drawn with the colours and layout from the config
+    pub fn with_config(num_tabs: usize, theme: Theme, layout: PaneLayout) -> UiState {
+        UiState {
+            match_view: layout.default_view,
+            theme,
+            layout,
+            ..UiState::new(num_tabs)
         }
     }
 
+    /// Focus a tab, adding the scroll state of any tabs which have been opened since
+    pub fn focus_tab(&mut self, idx: usize) {
+        if self.scrd_scroll.len() <= idx {
+            self.scrd_scroll.resize(idx + 1, (0, 0));
+            self.comm_scroll.resize(idx + 1, (0, 0));
+        }
+        self.focused_tab = idx;
+    }
+
+    /// Flash the title of a match tab until it is selected
+    pub fn flash(&mut self, match_id: u32) {
+        self.flashing.insert(match_id);
+    }
+
+    /// Show or hide the match browser
+    pub fn toggle_browser(&mut self) {
+        self.show_browser = !self.show_browser;
+        self.browser_selected = 0;
+    }
+
+    /// Select the next match in the match browser
+    pub fn add_browser_selected(&mut self, value: usize, num_entries: usize) {
+        self.browser_selected = self
+            .browser_selected
+            .saturating_add(value)
+            .min(num_entries.saturating_sub(1));
+    }
+
+    /// Select the previous match in the match browser
+    pub fn sub_browser_selected(&mut self, value: usize) {
+        self.browser_selected = self.browser_selected.saturating_sub(value);
+    }
+
     /// Add a value to the `focused_tab` property
     pub fn add_focused_tab(&mut self, value: usize) {
         if self.focused_tab < (self.scrd_scroll.len() - 1) {
@@ -49,7 +152,10 @@ impl UiState {
     /// Increment the scroll value of a particular tab index
     pub fn add_scrd_scroll(&mut self, value: u16) {
         // Should not cross maximum lines present in the scorecard
-        if self.scrd_scroll[self.focused_tab].0 < (self.scrd_scroll[self.focused_tab].1 - 2) {
+        // The scorecard has no lines until it is drawn for the first time
+        if self.scrd_scroll[self.focused_tab].0
+            < self.scrd_scroll[self.focused_tab].1.saturating_sub(2)
+        {
             self.scrd_scroll[self.focused_tab].0 =
                 self.scrd_scroll[self.focused_tab].0.saturating_add(value);
         }
@@ -71,12 +177,118 @@ impl UiState {
         self.scrd_scroll[self.focused_tab].1 = value;
     }
 
+    /// Scroll the focused pane down, or select a later match when the match browser is shown
+    pub fn scroll_down(&mut self, lines: u16, num_browser_entries: usize) {
+        if self.show_browser {
+            self.add_browser_selected(lines as usize, num_browser_entries);
+            return;
+        }
+        for _ in 0..lines {
+            match self.focused_pane {
+                Pane::Scorecard => self.add_scrd_scroll(1),
+                Pane::Commentary => self.add_comm_scroll(1),
+            }
+        }
+    }
+
+    /// Scroll the focused pane up, or select an earlier match when the match browser is shown
+    pub fn scroll_up(&mut self, lines: u16) {
+        if self.show_browser {
+            self.sub_browser_selected(lines as usize);
+            return;
+        }
+        match self.focused_pane {
+            Pane::Scorecard => self.sub_scrd_scroll(lines),
+            Pane::Commentary => self.sub_comm_scroll(lines),
+        }
+    }
+
+    /// Scroll the focused pane to the top, or select the first match in the match browser
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_up(u16::MAX);
+    }
+
+    /// Scroll the focused pane to the bottom, or select the last match in the match browser
+    pub fn scroll_to_bottom(&mut self, num_browser_entries: usize) {
+        if self.show_browser {
+            self.add_browser_selected(usize::MAX, num_browser_entries);
+            return;
+        }
+        let tab = self.focused_tab;
+        match self.focused_pane {
+            Pane::Scorecard => self.scrd_scroll[tab].0 = self.scrd_scroll[tab].1.saturating_sub(2),
+            Pane::Commentary => self.comm_scroll[tab].0 = self.comm_scroll[tab].1.saturating_sub(1),
+        }
+    }
+
+    /// Switch the pane that receives the scroll key presses
+    pub fn toggle_focused_pane(&mut self) {
+        self.focused_pane = match self.focused_pane {
+            Pane::Scorecard => Pane::Commentary,
+            Pane::Commentary => Pane::Scorecard,
+        };
+    }
+
+    /// Switch to the next view shown below the commentary
+    pub fn next_match_view(&mut self) {
+        self.match_view = match self.match_view {
+            MatchView::Scorecard => MatchView::Worm,
+            MatchView::Worm => MatchView::Manhattan,
+            MatchView::Manhattan => MatchView::Scorecard,
+        };
+    }
+
+    /// Switch the Manhattan chart to the next innings, going back to the first innings
+    /// after the last one
+    pub fn next_manhattan_innings(&mut self, num_innings: usize) {
+        if num_innings == 0 {
+            return;
+        }
+        let curr = self.manhattan_innings.unwrap_or(num_innings - 1);
+        self.manhattan_innings = Some((curr + 1) % num_innings);
+    }
+
+    /// Increment the commentary scroll value of a particular tab index
+    pub fn add_comm_scroll(&mut self, value: u16) {
+        // Should not cross maximum lines present in the commentary
+        if self.comm_scroll[self.focused_tab].0
+            < self.comm_scroll[self.focused_tab].1.saturating_sub(1)
+        {
+            self.comm_scroll[self.focused_tab].0 =
+                self.comm_scroll[self.focused_tab].0.saturating_add(value);
+        }
+    }
+
+    /// Decrement the commentary scroll value of a particular tab index
+    pub fn sub_comm_scroll(&mut self, value: u16) {
+        self.comm_scroll[self.focused_tab].0 =
+            self.comm_scroll[self.focused_tab].0.saturating_sub(value);
+    }
+
+    /// Get the current commentary scroll value
+    pub fn current_comm_scroll_value(&self) -> u16 {
+        self.comm_scroll[self.focused_tab].0
+    }
+
+    /// Update the max commentary scroll length allowed for a tab
+    pub fn update_comm_scroll_max_length(&mut self, value: u16) {
+        self.comm_scroll[self.focused_tab].1 = value;
+    }
+
     /// Update the scorecard scroll vector if any of the matches are not live anymore.
-    /// Removes the non-live matches
-    pub fn update_on_tick(&mut self, invalid_idx: &[usize]) {
+    /// Removes the non-live matches and adds the matches which went live at the end
+    pub fn update_on_tick(&mut self, invalid_idx: &[usize], num_tabs: usize) {
         for i in invalid_idx {
             self.scrd_scroll.remove(*i);
+            self.comm_scroll.remove(*i);
         }
+        self.scrd_scroll.resize(num_tabs, (0, 0));
+        self.comm_scroll.resize(num_tabs, (0, 0));
+
+        // The focused match may have been removed
+        self.focused_tab = self
+            .focused_tab
+            .min(self.scrd_scroll.len().saturating_sub(1));
     }
 }
 
@@ -86,21 +298,202 @@ where
     B: Backend,
 {
     let chunks = Layout::default()
-        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
         .split(f.size());
 
+    if let Some(mi) = app.matches_info.get(ui_state.focused_tab) {
+        ui_state.flashing.remove(&mi.cricbuzz_match_id);
+    }
+
     let match_names = app.get_all_matches_short_names();
     let tab_titles = match_names
         .iter()
-        .map(|m| Spans::from(Span::styled(m.as_str(), Style::default().fg(Color::White))))
+        .enumerate()
+        .map(|(idx, m)| {
+            let color = if app.is_favourite(idx) {
+                ui_state.theme.favourite
+            } else {
+                ui_state.theme.tab
+            };
+            let mut style = Style::default().fg(color);
+            if ui_state
+                .flashing
+                .contains(&app.matches_info[idx].cricbuzz_match_id)
+            {
+                style = style.add_modifier(Modifier::SLOW_BLINK | Modifier::REVERSED);
+            }
+            if app.matches_info[idx].refresh_failed {
+                // Marks the matches whose details are out of date, whichever tab is focused
+                Spans::from(vec![
+                    Span::styled(m.as_str(), style),
+                    Span::styled(" !", Style::default().fg(Color::Red)),
+                ])
+            } else {
+                Spans::from(Span::styled(m.as_str(), style))
+            }
+        })
         .collect();
 
+    let title = if app.refreshing {
+        "Matches (refreshing…)"
+    } else {
+        "Matches"
+    };
     let tabs = Tabs::new(tab_titles)
-        .block(Block::default().borders(Borders::ALL).title("Matches"))
-        .highlight_style(Style::default().fg(Color::Green))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().fg(ui_state.theme.highlight))
         .select(ui_state.focused_tab);
     f.render_widget(tabs, chunks[0]);
-    draw_tab(f, chunks[1], app, ui_state);
+    if ui_state.show_browser {
+        draw_browser(f, chunks[1], app, ui_state);
+    } else {
+        draw_tab(f, chunks[1], app, ui_state);
+    }
+    draw_status_bar(f, chunks[2], app, ui_state);
+}
+
+/// Draws a single line with the time the focused match was last refreshed and the most
+/// recent error
+fn draw_status_bar<B>(f: &mut Frame<B>, area: Rect, app: &App, ui_state: &UiState)
+where
+    B: Backend,
+{
+    let mut spans = vec![];
+
+    // There are no matches while the browser is opened as no match is live
+    match app.matches_info.get(ui_state.focused_tab) {
+        Some(mi) => {
+            spans.push(Span::styled(
+                format!(" Updated {}", format_elapsed(mi.last_refresh)),
+                Style::default().fg(Color::DarkGray),
+            ));
+            if !mi.cricbuzz_info.missing_fields.is_empty() {
+                spans.push(Span::styled(
+                    format!(" | Missing: {}", mi.cricbuzz_info.missing_fields.join(", ")),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+        }
+        None => spans.push(Span::styled(
+            " No live matches",
+            Style::default().fg(Color::DarkGray),
+        )),
+    }
+
+    if let Some(err) = &app.last_error {
+        let source = err
+            .match_id
+            .and_then(|id| app.match_short_name(id))
+            .map(|name| format!("{}: ", name))
+            .unwrap_or_default();
+        spans.push(Span::styled(
+            format!(
+                " | Error {}: {}{}",
+                format_elapsed(err.time),
+                source,
+                err.error
+            ),
+            Style::default().fg(Color::Red),
+        ));
+    }
+
+    f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
+
+/// Returns how long ago a time was, in the largest whole unit. Eg. "5m ago"
+fn format_elapsed(time: SystemTime) -> String {
+    let secs = time.elapsed().map(|d| d.as_secs()).unwrap_or_default();
+    match secs {
+        0..=4 => "just now".to_string(),
+        5..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        _ => format!("{}h ago", secs / 3600),
+    }
+}
+
+/// Draws the list of upcoming and recently completed matches, which can be opened in a
+/// new tab
+fn draw_browser<B>(f: &mut Frame<B>, area: Rect, app: &App, ui_state: &UiState)
+where
+    B: Backend,
+{
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Upcoming and recent matches");
+
+    let entries = match &app.browser {
+        Some(entries) if !entries.is_empty() => entries,
+        Some(_) => {
+            let paragraph = Paragraph::new("No upcoming or recent matches").block(block);
+            f.render_widget(paragraph, area);
+            return;
+        }
+        None => {
+            f.render_widget(Paragraph::new("Loading…").block(block), area);
+            return;
+        }
+    };
+
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|e| {
+            let (tag, color, detail) = match &e.status {
+                BrowserStatus::Upcoming(start) => (
+                    "[Upcoming]",
+                    Color::Yellow,
+                    format_start_time(*start, &Local),
+                ),
+                BrowserStatus::Completed(result) => ("[Result]  ", Color::Green, result.clone()),
+            };
+            ListItem::new(vec![
+                Spans::from(vec![
+                    Span::styled(tag, Style::default().fg(color)),
+                    Span::raw(" "),
+                    Span::styled(
+                        e.short_name.as_str(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(" - "),
+                    Span::raw(e.description.as_str()),
+                ]),
+                Spans::from(Span::styled(
+                    format!("           {}", detail),
+                    Style::default().fg(Color::Gray),
+                )),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray));
+    let mut state = ListState::default();
+    state.select(Some(ui_state.browser_selected.min(entries.len() - 1)));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// Returns the start time of a match in a time zone. Eg. "Sun 23 May 09:00"
+///
+/// # Arguments
+///
+/// * `start` - Milliseconds since the Unix epoch
+/// * `tz` - Time zone in which the time is shown
+fn format_start_time<Tz>(start: u64, tz: &Tz) -> String
+where
+    Tz: TimeZone,
+    Tz::Offset: Display,
+{
+    match tz.timestamp_millis_opt(start as i64).single() {
+        Some(time) => time.format("%a %d %b %H:%M").to_string(),
+        None => "Start time not known".to_string(),
+    }
 }
 
 /// Draws the tabs which are the short forms of the live matches
@@ -112,7 +505,8 @@ where
         .constraints(
             [
                 Constraint::Length(5),
-                Constraint::Length(9),
+                Constraint::Length(ui_state.layout.live_feed_height),
+                Constraint::Length(ui_state.layout.commentary_height),
                 Constraint::Percentage(100),
             ]
             .as_ref(),
@@ -124,8 +518,63 @@ where
     let summ_block = Block::default().borders(Borders::ALL).title("Overview");
     let paragraph = Paragraph::new(scores).block(summ_block);
     f.render_widget(paragraph, chunks[0]);
-    draw_live_feed(f, chunks[1], app, ui_state);
-    draw_scorecard(f, chunks[2], app, ui_state);
+
+    // Finished matches show their result in place of the live feed
+    let view_area = if app.matches_info[ui_state.focused_tab]
+        .cricbuzz_info
+        .is_complete()
+    {
+        let result_area = Rect {
+            height: 4,
+            ..chunks[1]
+        };
+        draw_result(f, result_area, app, ui_state);
+        Rect {
+            y: result_area.bottom(),
+            height: area.bottom().saturating_sub(result_area.bottom()),
+            ..area
+        }
+    } else {
+        draw_live_feed(f, chunks[1], app, ui_state);
+        if ui_state.layout.commentary_height > 0 {
+            draw_commentary(f, chunks[2], app, ui_state);
+        }
+        chunks[3]
+    };
+
+    match ui_state.match_view {
+        MatchView::Scorecard => draw_scorecard(f, view_area, app, ui_state),
+        MatchView::Worm => draw_worm_chart(f, view_area, app, ui_state),
+        MatchView::Manhattan => draw_manhattan_chart(f, view_area, app, ui_state),
+    }
+}
+
+/// Draws the result and the players of the match of a finished match
+fn draw_result<B>(f: &mut Frame<B>, area: Rect, app: &App, ui_state: &UiState)
+where
+    B: Backend,
+{
+    let curr_match = app.current_match_cricbuzz_info(ui_state.focused_tab);
+
+    let mut text = vec![Spans::from(Span::styled(
+        curr_match.result_summary(),
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    let players = curr_match.players_of_the_match();
+    if !players.is_empty() {
+        text.push(Spans::from(vec![
+            Span::styled(
+                "Player of the match: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(players.join(", ")),
+        ]));
+    }
+
+    let block = Block::default().borders(Borders::ALL).title("Result");
+    f.render_widget(Paragraph::new(text).block(block), area);
 }
 
 /// Draws the part showing the currently playing batsmen and bowlers, similar to cricbuzz
@@ -144,7 +593,7 @@ where
         Row::new(vec!["Batsman", "R", "B", "4", "6", "SR"])
             .style(Style::default().add_modifier(Modifier::BOLD)),
         Row::new(vec![
-            curr_match.bat_striker_name().to_string() + " *",
+            striker_name(curr_match.bat_striker_name()),
             curr_match.bat_striker_runs().to_string(),
             curr_match.bat_striker_balls().to_string(),
             curr_match.bat_striker_fours().to_string(),
@@ -163,7 +612,7 @@ where
         Row::new(vec!["Bowler", "O", "M", "R", "W", "ECO"])
             .style(Style::default().add_modifier(Modifier::BOLD)),
         Row::new(vec![
-            curr_match.bowl_striker_name().to_string() + " *",
+            striker_name(curr_match.bowl_striker_name()),
             curr_match.bowl_striker_ovs().to_string(),
             curr_match.bowl_striker_maidens().to_string(),
             curr_match.bowl_striker_runs().to_string(),
@@ -179,7 +628,7 @@ where
             curr_match.bowl_non_striker_econ().to_string(),
         ]),
     ])
-    .style(Style::default().fg(Color::White))
+    .style(Style::default().fg(ui_state.theme.text))
     .block(Block::default().borders(Borders::ALL).title("Live"))
     .widths(&[
         Constraint::Length(25),
@@ -193,7 +642,10 @@ where
     f.render_widget(table, chunks[0]);
 
     // Drawing Key Stats to the right
-    let mut key_stats: Vec<Spans> = vec![];
+    let mut key_stats: Vec<Spans> = match app.matches_info[ui_state.focused_tab].chase() {
+        Some(chase) => get_chase_lines(&chase),
+        None => vec![],
+    };
 
     key_stats.push(Spans::from(vec![
         Span::styled(
@@ -231,37 +683,185 @@ where
     ]));
 
     let key_stats_block = Block::default().borders(Borders::ALL).title("Key Stats");
-    let key_stats_para = Paragraph::new(key_stats)
-        .block(key_stats_block)
-        .wrap(Wrap { trim: true });
-    f.render_widget(key_stats_para, chunks[1]);
+    let key_stats_area = key_stats_block.inner(chunks[1]);
+    f.render_widget(key_stats_block, chunks[1]);
+
+    let projected = app.matches_info[ui_state.focused_tab].projected_scores();
+    let stats_chunks = if projected.is_empty() {
+        vec![key_stats_area]
+    } else {
+        Layout::default()
+            .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+            .split(key_stats_area)
+    };
+
+    let key_stats_para = Paragraph::new(key_stats).wrap(Wrap { trim: true });
+    f.render_widget(key_stats_para, stats_chunks[0]);
+
+    if !projected.is_empty() {
+        draw_projected_scores(f, stats_chunks[1], &projected);
+    }
 }
 
-/// Renders the scores of the teams that are playing
-fn get_match_summary_info<'a>(app: &'a App, ui_state: &'a mut UiState) -> Vec<Spans<'a>> {
-    let match_info = app.current_match_cricbuzz_info(ui_state.focused_tab);
-    let msd = &match_info.miniscore.match_score_details;
-    let mut scores = vec![];
+/// Returns the chase equation, the wickets in hand and how the current run rate compares
+/// with the required run rate. Eg. "Need 217 runs from 256 balls (RRR 5.09)"
+fn get_chase_lines(chase: &Chase) -> Vec<Spans<'static>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
 
-    if msd.match_format == "TEST" {
-        get_test_match_summary_info(&mut scores, app, ui_state);
-    } else if msd.match_format == "ODI" || msd.match_format == "T20" {
-        get_lim_ovs_match_summary_info(&mut scores, app, ui_state);
+    let mut equation = format!("Need {} runs", chase.runs_needed);
+    if let Some(balls) = chase.balls_remaining {
+        equation.push_str(&format!(" from {} balls", balls));
+    }
+    if let Some(rrr) = chase.required_run_rate {
+        equation.push_str(&format!(" (RRR {:.2})", rrr));
+    }
+
+    let mut rates = vec![
+        Span::styled("Wkts in hand: ", bold),
+        Span::from(format!("{}  ", chase.wickets_in_hand)),
+        Span::styled("CRR: ", bold),
+    ];
+    match chase.required_run_rate {
+        Some(rrr) => {
+            // Green while the batting team is scoring faster than it needs to
+            let color = if chase.current_run_rate >= rrr {
+                Color::Green
+            } else {
+                Color::Red
+            };
+            rates.push(Span::styled(
+                format!("{:.2}", chase.current_run_rate),
+                Style::default().fg(color),
+            ));
+            rates.push(Span::styled(" vs RRR: ", bold));
+            rates.push(Span::from(format!("{:.2}", rrr)));
+        }
+        None => rates.push(Span::from(format!("{:.2}", chase.current_run_rate))),
     }
 
+    vec![
+        Spans::from(Span::styled(
+            equation,
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Spans::from(rates),
+    ]
+}
+
+/// Draws the totals the innings would end with at different run rates
+fn draw_projected_scores<B>(f: &mut Frame<B>, area: Rect, projected: &[ProjectedScore])
+where
+    B: Backend,
+{
+    let header =
+        std::iter::once("Proj.".to_string()).chain(projected.iter().map(|p| p.label.to_string()));
+    let run_rates = std::iter::once("RR".to_string())
+        .chain(projected.iter().map(|p| format!("{:.2}", p.run_rate)));
+    let scores =
+        std::iter::once("Score".to_string()).chain(projected.iter().map(|p| p.score.to_string()));
+
+    let widths = [Constraint::Length(6); 7];
+    let table = Table::new(vec![
+        Row::new(header).style(Style::default().add_modifier(Modifier::BOLD)),
+        Row::new(run_rates),
+        Row::new(scores),
+    ])
+    .widths(&widths[..=projected.len()]);
+
+    f.render_widget(table, area);
+}
+
+/// Renders the scores of the teams that are playing along with the match status
+fn get_match_summary_info<'a>(app: &'a App, ui_state: &UiState) -> Vec<Spans<'a>> {
+    let match_info = app.current_match_cricbuzz_info(ui_state.focused_tab);
+    let msd = &match_info.miniscore.match_score_details;
+    let mut scores = get_match_score_lines(app, ui_state.focused_tab);
+
     scores.push(Spans::from(Span::styled(
         msd.custom_status.as_str(),
         Style::default()
-            .fg(Color::LightRed)
+            .fg(ui_state.theme.status)
             .add_modifier(Modifier::DIM),
     )));
 
     scores
 }
 
+/// Marks the batsman on strike or the bowler currently bowling, unless they are not known
+fn striker_name(name: &str) -> String {
+    if name.is_empty() {
+        String::new()
+    } else {
+        format!("{} *", name)
+    }
+}
+
+/// Returns the score line of each team that is playing
+///
+/// # Arguments
+///
+/// * `app` - The app holding all the live matches
+/// * `idx` - Index of the match in the app
+pub fn get_match_score_lines(app: &App, idx: usize) -> Vec<Spans<'_>> {
+    let match_info = app.current_match_cricbuzz_info(idx);
+    let msd = &match_info.miniscore.match_score_details;
+    let mut scores = vec![];
+
+    if !has_innings_teams(msd) {
+        get_innings_score_lines(&mut scores, msd);
+    } else if msd.match_format == "TEST" {
+        get_test_match_summary_info(&mut scores, app, idx);
+    } else if msd.match_format == "ODI" || msd.match_format == "T20" {
+        get_lim_ovs_match_summary_info(&mut scores, app, idx);
+    }
+
+    scores
+}
+
+/// Checks that the batting and bowling teams of the latest innings are present along with
+/// the innings they have played, which the match summaries rely on. Cricbuzz leaves a few
+/// of them out at times, like during innings breaks.
+fn has_innings_teams(msd: &CricbuzzMiniscoreMatchScoreDetails) -> bool {
+    let total_inngs = msd.innings_score_list.len();
+    // There is nothing to summarise between the toss and the first ball
+    if total_inngs == 0 {
+        return false;
+    }
+    if total_inngs == 1 {
+        return true;
+    }
+
+    let team_info = match msd.match_team_info.get(total_inngs - 1) {
+        Some(t) => t,
+        None => return false,
+    };
+    let innings_of = |team: &str| {
+        msd.innings_score_list
+            .iter()
+            .filter(|i| i.bat_team_name == team)
+            .count()
+    };
+
+    // The batting team has played every other innings starting from the latest one
+    innings_of(&team_info.batting_team_short_name) >= total_inngs.div_ceil(2)
+        && innings_of(&team_info.bowling_team_short_name) >= total_inngs / 2
+}
+
+/// Builds a plain score line for every innings, without telling who is batting
+fn get_innings_score_lines(scores: &mut Vec<Spans>, msd: &CricbuzzMiniscoreMatchScoreDetails) {
+    for inns_score in &msd.innings_score_list {
+        scores.push(Spans::from(format!(
+            "{} {}/{} ({})",
+            inns_score.bat_team_name, inns_score.score, inns_score.wickets, inns_score.overs
+        )));
+    }
+}
+
 /// Builds the score summary for a test match
-fn get_test_match_summary_info(scores: &mut Vec<Spans>, app: &App, ui_state: &mut UiState) {
-    let match_info = app.current_match_cricbuzz_info(ui_state.focused_tab);
+fn get_test_match_summary_info(scores: &mut Vec<Spans>, app: &App, idx: usize) {
+    let match_info = app.current_match_cricbuzz_info(idx);
     let msd = &match_info.miniscore.match_score_details;
 
     let total_inngs = msd.innings_score_list.len();
@@ -300,7 +900,7 @@ fn get_test_match_summary_info(scores: &mut Vec<Spans>, app: &App, ui_state: &mu
         for inns_score in &msd.innings_score_list {
             teams
                 .entry(inns_score.bat_team_name.as_str())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(inns_score);
         }
 
@@ -333,7 +933,7 @@ fn get_test_match_summary_info(scores: &mut Vec<Spans>, app: &App, ui_state: &mu
         for inns_score in &msd.innings_score_list {
             teams
                 .entry(inns_score.bat_team_name.as_str())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(inns_score);
         }
 
@@ -368,7 +968,7 @@ fn get_test_match_summary_info(scores: &mut Vec<Spans>, app: &App, ui_state: &mu
         for inns_score in &msd.innings_score_list {
             teams
                 .entry(inns_score.bat_team_name.as_str())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(inns_score);
         }
 
@@ -399,8 +999,8 @@ fn get_test_match_summary_info(scores: &mut Vec<Spans>, app: &App, ui_state: &mu
 }
 
 /// Builds the score summary for an ODI or a T20 match
-fn get_lim_ovs_match_summary_info(scores: &mut Vec<Spans>, app: &App, ui_state: &mut UiState) {
-    let match_info = app.current_match_cricbuzz_info(ui_state.focused_tab);
+fn get_lim_ovs_match_summary_info(scores: &mut Vec<Spans>, app: &App, idx: usize) {
+    let match_info = app.current_match_cricbuzz_info(idx);
     let msd = &match_info.miniscore.match_score_details;
 
     let total_inngs = msd.innings_score_list.len();
@@ -426,7 +1026,7 @@ fn get_lim_ovs_match_summary_info(scores: &mut Vec<Spans>, app: &App, ui_state:
         for inns_score in &msd.innings_score_list {
             teams
                 .entry(inns_score.bat_team_name.as_str())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(inns_score);
         }
 
@@ -461,7 +1061,10 @@ where
     let text = format_scorecard_info(scorecard);
     ui_state.update_scroll_max_length(text.len() as u16);
 
-    let block = Block::default().borders(Borders::ALL).title("Scorecard");
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Scorecard")
+        .border_style(pane_border_style(ui_state, Pane::Scorecard));
 
     let paragraph = Paragraph::new(text)
         .block(block)
@@ -470,12 +1073,306 @@ where
     f.render_widget(paragraph, area);
 }
 
+/// Renders the cumulative runs after every over of each innings of a limited overs match,
+/// with the overs in which wickets fell marked
+fn draw_worm_chart<B>(f: &mut Frame<B>, area: Rect, app: &App, ui_state: &mut UiState)
+where
+    B: Backend,
+{
+    let mi = &app.matches_info[ui_state.focused_tab];
+    let innings = mi.innings_over_history();
+    // Only the last few overs are sent by Cricbuzz, so overs bowled before the match was
+    // first fetched are missing
+    let title = if innings.iter().any(|overs| overs[0].over > 1) {
+        "Worm - partial, overs before the match was opened are missing"
+    } else {
+        "Worm"
+    };
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    let total_overs = match mi.cricbuzz_info.format_total_overs() {
+        Some(overs) => overs,
+        None => {
+            let paragraph =
+                Paragraph::new("Worm chart is only available for limited overs matches")
+                    .block(block);
+            f.render_widget(paragraph, area);
+            return;
+        }
+    };
+
+    // Every innings starts from zero runs, unless its first few overs were never fetched
+    let scores: Vec<Vec<(f64, f64)>> = innings
+        .iter()
+        .map(|overs| {
+            let start = if overs[0].over == 1 {
+                Some((0.0, 0.0))
+            } else {
+                None
+            };
+            start
+                .into_iter()
+                .chain(overs.iter().map(|o| (o.over as f64, o.score as f64)))
+                .collect()
+        })
+        .collect();
+    let wickets: Vec<(f64, f64)> = innings
+        .iter()
+        .flat_map(|overs| overs.iter())
+        .filter(|o| o.wickets > 0)
+        .map(|o| (o.over as f64, o.score as f64))
+        .collect();
+
+    let mut datasets: Vec<Dataset> = innings
+        .iter()
+        .zip(scores.iter())
+        .enumerate()
+        .map(|(i, (overs, data))| {
+            Dataset::default()
+                .name(overs[0].bat_team_name.as_str())
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(INNINGS_COLORS[i % INNINGS_COLORS.len()]))
+                .data(data)
+        })
+        .collect();
+    datasets.push(
+        Dataset::default()
+            .name("Wicket")
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            .data(&wickets),
+    );
+
+    // Round the runs axis up to the next multiple of 50
+    let max_score = innings
+        .iter()
+        .flat_map(|overs| overs.iter())
+        .map(|o| o.score)
+        .max()
+        .unwrap_or(0);
+    let max_runs = (max_score / 50 + 1) * 50;
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .hidden_legend_constraints((Constraint::Ratio(1, 3), Constraint::Ratio(1, 2)))
+        .x_axis(
+            Axis::default()
+                .title("Overs")
+                .bounds([0.0, total_overs as f64])
+                .labels(axis_labels(total_overs)),
+        )
+        .y_axis(
+            Axis::default()
+                .title("Runs")
+                .bounds([0.0, max_runs as f64])
+                .labels(axis_labels(max_runs)),
+        );
+    f.render_widget(chart, area);
+}
+
+/// Renders the runs scored in every over of an innings as bars, highlighting the overs in
+/// which wickets fell and the powerplay overs
+fn draw_manhattan_chart<B>(f: &mut Frame<B>, area: Rect, app: &App, ui_state: &mut UiState)
+where
+    B: Backend,
+{
+    const BAR_WIDTH: u16 = 3;
+    const BAR_GAP: u16 = 1;
+
+    let mi = &app.matches_info[ui_state.focused_tab];
+    let innings = mi.innings_over_history();
+    let block = Block::default().borders(Borders::ALL);
+
+    if innings.is_empty() {
+        let paragraph =
+            Paragraph::new("No overs have been completed yet").block(block.title("Manhattan"));
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let idx = ui_state
+        .manhattan_innings
+        .map_or(innings.len() - 1, |i| i.min(innings.len() - 1));
+    let overs = innings[idx];
+    // Overs bowled before the match was first fetched are missing
+    let partial = if overs[0].over > 1 {
+        format!("(from over {}) ", overs[0].over)
+    } else {
+        String::new()
+    };
+
+    // Only the latest overs are shown if all of them do not fit
+    let max_bars = (block.inner(area).width / (BAR_WIDTH + BAR_GAP)) as usize;
+    let overs = &overs[overs.len().saturating_sub(max_bars)..];
+
+    let labels: Vec<String> = overs.iter().map(|o| o.over.to_string()).collect();
+    let data: Vec<(&str, u64)> = labels
+        .iter()
+        .zip(overs.iter())
+        .map(|(label, o)| (label.as_str(), o.runs as u64))
+        .collect();
+    let bar_colors = overs
+        .iter()
+        .map(|o| {
+            if o.wickets > 0 {
+                Color::Red
+            } else if o.powerplay {
+                Color::Yellow
+            } else {
+                Color::Cyan
+            }
+        })
+        .collect();
+
+    let title = Spans::from(vec![
+        Span::raw(format!(
+            "Manhattan - {} innings {} {}",
+            overs[0].bat_team_name, overs[0].innings_id, partial
+        )),
+        Span::styled("■ wicket ", Style::default().fg(Color::Red)),
+        Span::styled("■ powerplay", Style::default().fg(Color::Yellow)),
+    ]);
+    let chart = BarChart::default()
+        .block(block.title(title))
+        .data(&data)
+        .bar_width(BAR_WIDTH)
+        .bar_gap(BAR_GAP)
+        // Scale the bars to at least a six every ball, so small overs look small
+        .max(data.iter().map(|d| d.1).max().unwrap_or(0).max(36));
+
+    f.render_widget(
+        ColouredBarChart {
+            chart,
+            bar_colors,
+            bar_width: BAR_WIDTH,
+            bar_gap: BAR_GAP,
+        },
+        area,
+    );
+}
+
+/// A bar chart with a colour for every bar, which tui's `BarChart` does not support.
+/// The chart is rendered as usual and then every bar is recoloured.
+struct ColouredBarChart<'a> {
+    /// Chart with a bordered block
+    chart: BarChart<'a>,
+    bar_colors: Vec<Color>,
+    bar_width: u16,
+    bar_gap: u16,
+}
+
+impl Widget for ColouredBarChart<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.chart.render(area, buf);
+
+        let inner = Block::default().borders(Borders::ALL).inner(area);
+        // The last row holds the labels
+        for (i, color) in self.bar_colors.iter().enumerate() {
+            let left = inner.left() + i as u16 * (self.bar_width + self.bar_gap);
+            for x in left..(left + self.bar_width).min(inner.right()) {
+                for y in inner.top()..inner.bottom().saturating_sub(1) {
+                    let cell = buf.get_mut(x, y);
+                    // Runs are written on top of the bar
+                    if cell.symbol.chars().all(|c| c.is_ascii_digit()) {
+                        cell.set_style(Style::default().fg(Color::Black).bg(*color));
+                    } else {
+                        cell.set_style(Style::default().fg(*color));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the labels at the start, middle and end of an axis
+fn axis_labels(max: u32) -> Vec<Span<'static>> {
+    [0, max / 2, max]
+        .iter()
+        .map(|v| Span::raw(v.to_string()))
+        .collect()
+}
+
+/// Renders the ball-by-ball commentary for a particular match, latest delivery first
+fn draw_commentary<B>(f: &mut Frame<B>, area: Rect, app: &App, ui_state: &mut UiState)
+where
+    B: Backend,
+{
+    let curr_match = app.current_match_cricbuzz_info(ui_state.focused_tab);
+    let text = format_commentary_info(curr_match.commentary_list());
+    ui_state.update_comm_scroll_max_length(text.len() as u16);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Commentary")
+        .border_style(pane_border_style(ui_state, Pane::Commentary));
+
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .wrap(Wrap { trim: true })
+        .scroll((ui_state.current_comm_scroll_value(), 0));
+    f.render_widget(paragraph, area);
+}
+
+/// Highlights the border of the pane which currently receives scroll key presses
+fn pane_border_style(ui_state: &UiState, pane: Pane) -> Style {
+    if ui_state.focused_pane == pane {
+        Style::default().fg(Color::Green)
+    } else {
+        Style::default()
+    }
+}
+
+/// Returns the commentary lines for display on the terminal, with boundaries and
+/// wickets highlighted
+///
+/// # Arguments
+///
+/// * `commentary` - A slice of the latest commentary entries, latest first
+fn format_commentary_info(commentary: &[CricbuzzCommentary]) -> Vec<Spans<'_>> {
+    let mut text = vec![];
+
+    for comm in commentary {
+        let style = if comm.is_wicket() {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else if comm.is_six() {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else if comm.is_four() {
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        let mut spans = vec![];
+        if let Some(ovs) = comm.over_number {
+            spans.push(Span::styled(format!("{:<6}", ovs), style));
+        }
+
+        for (segment, bold) in comm.comm_text_segments() {
+            if bold {
+                spans.push(Span::styled(segment, style.add_modifier(Modifier::BOLD)));
+            } else {
+                spans.push(Span::styled(segment, style));
+            }
+        }
+
+        text.push(Spans::from(spans));
+    }
+
+    text
+}
+
 /// Returns the structured scorecard information for display on the terminal
 ///
 /// # Arguments
 ///
 /// * `scorecard` - A slice of all the innings information in a match
-fn format_scorecard_info(scorecard: &[MatchInningsInfo]) -> Vec<Spans> {
+fn format_scorecard_info(scorecard: &[MatchInningsInfo]) -> Vec<Spans<'_>> {
     let mut text = vec![];
 
     for (ino, info) in scorecard.iter().enumerate().rev() {
@@ -501,6 +1398,52 @@ fn format_scorecard_info(scorecard: &[MatchInningsInfo]) -> Vec<Spans> {
             )));
         }
 
+        if !info.extras.text.is_empty() {
+            text.push(Spans::from(format!(
+                "{:<30} {:<60} {:<3}",
+                "Extras",
+                format!("({})", info.extras_details),
+                info.extras
+            )));
+        }
+
+        if !info.total.text.is_empty() {
+            text.push(Spans::from(Span::styled(
+                format!(
+                    "{:<30} {:<60} {:<3}",
+                    "Total",
+                    format!("({})", info.total_details),
+                    info.total
+                ),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+        }
+
+        if !info.yet_to_bat.is_empty() {
+            text.push(Spans::from(vec![
+                Span::styled(
+                    format!("{:<30} ", "Yet to Bat"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::from(info.yet_to_bat.join(", ")),
+            ]));
+        }
+
+        if !info.fall_of_wickets.is_empty() {
+            text.push(Spans::from(""));
+            text.push(Spans::from(Span::styled(
+                "Fall of Wickets",
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            text.push(Spans::from(
+                info.fall_of_wickets
+                    .iter()
+                    .map(|fow| fow.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ));
+        }
+
         text.push(Spans::from(""));
 
         text.push(Spans::from(Span::styled(
@@ -526,267 +1469,200 @@ fn format_scorecard_info(scorecard: &[MatchInningsInfo]) -> Vec<Spans> {
 
 #[cfg(test)]
 mod test {
-    use std::fs;
-
     use crate::{
-        app::{create_match_info, parse_scorecard_from_file, App},
-        cricbuzz_api::CricbuzzJson,
-        display::ui::{draw_ui, UiState},
+        app::{App, BrowserEntry, BrowserStatus},
+        display::ui::{draw_ui, format_start_time, MatchView, UiState},
+        test_utils::{load_json, load_match},
     };
-    use tui::{backend::TestBackend, buffer::Cell, Terminal};
-
-    // Path is relative to where `cargo test` command is run
-    const TEST_FILES_PATH: &str = "./tests/data/";
+    use tui::{backend::TestBackend, buffer::Buffer, style::Color, Terminal};
 
     // Path is relative to where the testfile is present
     const SNAPSHOTS_PATH: &str = "../../tests/snapshots";
 
-    fn get_terminal(width: u16, height: u16) -> Terminal<TestBackend> {
-        let backend = TestBackend::new(width, height);
-        let terminal = Terminal::new(backend).unwrap();
-        terminal
+    /// Draws the UI on a terminal of the given size and compares it with the snapshot named
+    /// after the test
+    macro_rules! assert_ui_snapshot {
+        ($app:expr, $width:expr, $height:expr, $ui_state:expr) => {{
+            let out = format_buffer(&draw($app, $width, $height, $ui_state));
+
+            let mut settings = insta::Settings::clone_current();
+            settings.set_snapshot_path(SNAPSHOTS_PATH);
+
+            settings.bind(|| {
+                insta::assert_display_snapshot!(out);
+            });
+        }};
+    }
+
+    /// Returns an app following the match saved in the test data directory
+    fn app_with_match(json: &str, scorecard: Option<&str>) -> App {
+        let mut app = App::default();
+        app.matches_info.push(load_match(json, scorecard));
+        app
     }
 
-    fn format_backend(out: Vec<Cell>, width: u16) -> String {
+    fn draw(app: &App, width: u16, height: u16, ui_state: &mut UiState) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| draw_ui(f, app, ui_state)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    fn format_buffer(buffer: &Buffer) -> String {
+        let width = buffer.area.width as usize;
         let mut s = String::new();
 
-        for (i, c) in out.iter().enumerate() {
-            if i != 0 && (i % (width as usize)) == 0 {
+        for (i, c) in buffer.content().iter().enumerate() {
+            if i != 0 && (i % width) == 0 {
                 s.push('\n');
-                s.push_str(c.symbol.as_str());
-            } else {
-                s.push_str(c.symbol.as_str());
             }
+            s.push_str(c.symbol.as_str());
         }
         s
     }
 
     #[test]
     fn test_odi_first_inngs_draw_ui() {
-        let mut app = App::default();
-
-        let scrd_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_odi_scorecard_first_innings.txt"
-        ))
-        .unwrap();
-        let json_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_odi_first_innings.json"
-        ))
-        .unwrap();
-
-        let json: CricbuzzJson = serde_json::from_str(&json_data).unwrap();
-        let mut scorecard = vec![];
-        parse_scorecard_from_file(&scrd_data, &mut scorecard);
-        let match_short_name = "BAN vs SL".to_string();
-        let api_link = "".to_string();
-
-        let match_info = create_match_info(match_short_name, 36096, api_link, json, scorecard);
-
-        app.matches_info.push(match_info);
-
-        let width = 125;
-        let height = 35;
-        let mut terminal = get_terminal(width, height);
-        let mut ui_state = UiState::new(1);
-
-        terminal
-            .draw(|mut f| draw_ui(&mut f, &app, &mut ui_state))
-            .unwrap();
-
-        let out = terminal.backend().buffer().content().to_vec();
-        let out = format_backend(out, width);
-
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
-
-        settings.bind(|| {
-            insta::assert_display_snapshot!(out);
-        });
+        let app = app_with_match(
+            "cricbuzz_odi_first_innings.json",
+            Some("cricbuzz_odi_scorecard_first_innings.txt"),
+        );
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
     }
 
     #[test]
     fn test_odi_second_inngs_draw_ui() {
-        let mut app = App::default();
-
-        let scrd_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_odi_scorecard_second_innings.txt"
-        ))
-        .unwrap();
-        let json_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_odi_second_innings.json"
-        ))
-        .unwrap();
-
-        let json: CricbuzzJson = serde_json::from_str(&json_data).unwrap();
-        let mut scorecard = vec![];
-        parse_scorecard_from_file(&scrd_data, &mut scorecard);
-        let match_short_name = "BAN vs SL".to_string();
-        let api_link = "".to_string();
-        let match_id = 36096;
-
-        let match_info = create_match_info(match_short_name, match_id, api_link, json, scorecard);
-
-        app.matches_info.push(match_info);
-
-        let width = 125;
-        let height = 35;
-        let mut terminal = get_terminal(width, height);
-        let mut ui_state = UiState::new(1);
-
-        terminal
-            .draw(|mut f| draw_ui(&mut f, &app, &mut ui_state))
-            .unwrap();
-
-        let out = terminal.backend().buffer().content().to_vec();
-        let out = format_backend(out, width);
+        let app = app_with_match(
+            "cricbuzz_odi_second_innings.json",
+            Some("cricbuzz_odi_scorecard_second_innings.txt"),
+        );
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
+    }
 
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
+    #[test]
+    fn test_test_first_inngs_draw_ui() {
+        let app = app_with_match(
+            "cricbuzz_test_first_innings.json",
+            Some("cricbuzz_test_scorecard_first_innings.txt"),
+        );
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
+    }
 
-        settings.bind(|| {
-            insta::assert_display_snapshot!(out);
-        });
+    #[test]
+    fn test_test_second_inngs_draw_ui() {
+        let app = app_with_match(
+            "cricbuzz_test_second_innings.json",
+            Some("cricbuzz_test_scorecard_second_innings.txt"),
+        );
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
     }
 
     #[test]
-    fn test_test_first_inngs_draw_ui() {
-        let mut app = App::default();
+    fn test_test_fourth_inngs_draw_ui() {
+        let app = app_with_match(
+            "cricbuzz_test_fourth_innings.json",
+            Some("cricbuzz_test_scorecard_fourth_innings.txt"),
+        );
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
+    }
 
-        let scrd_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_test_scorecard_first_innings.txt"
-        ))
-        .unwrap();
-        let json_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_test_first_innings.json"
-        ))
-        .unwrap();
-
-        let json: CricbuzzJson = serde_json::from_str(&json_data).unwrap();
-        let mut scorecard = vec![];
-        parse_scorecard_from_file(&scrd_data, &mut scorecard);
-        let match_short_name = "ENG vs NZ".to_string();
-        let api_link = "".to_string();
-        let match_id = 33806;
-
-        let match_info = create_match_info(match_short_name, match_id, api_link, json, scorecard);
+    #[test]
+    fn test_odi_worm_chart_draw_ui() {
+        let mut match_info = load_match("cricbuzz_odi_first_innings.json", None);
+        // Overs of the first innings are remembered once the second innings starts
+        match_info.cricbuzz_info = load_json("cricbuzz_odi_second_innings.json");
+        match_info.update_over_history();
+        assert_eq!(match_info.innings_over_history().len(), 2);
 
+        let mut app = App::default();
         app.matches_info.push(match_info);
-
-        let width = 125;
-        let height = 35;
-        let mut terminal = get_terminal(width, height);
         let mut ui_state = UiState::new(1);
+        ui_state.next_match_view();
 
-        terminal
-            .draw(|mut f| draw_ui(&mut f, &app, &mut ui_state))
-            .unwrap();
-
-        let out = terminal.backend().buffer().content().to_vec();
-        let out = format_backend(out, width);
-
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
-
-        settings.bind(|| {
-            insta::assert_display_snapshot!(out);
-        });
+        assert_ui_snapshot!(&app, 125, 50, &mut ui_state);
     }
 
     #[test]
-    fn test_test_second_inngs_draw_ui() {
-        let mut app = App::default();
-
-        let scrd_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_test_scorecard_second_innings.txt"
-        ))
-        .unwrap();
-        let json_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_test_second_innings.json"
-        ))
-        .unwrap();
-
-        let json: CricbuzzJson = serde_json::from_str(&json_data).unwrap();
-        let mut scorecard = vec![];
-        parse_scorecard_from_file(&scrd_data, &mut scorecard);
-        let match_short_name = "ENG vs NZ".to_string();
-        let api_link = "".to_string();
-        let match_id = 33806;
-
-        let match_info = create_match_info(match_short_name, match_id, api_link, json, scorecard);
-
-        app.matches_info.push(match_info);
-
-        let width = 125;
-        let height = 35;
-        let mut terminal = get_terminal(width, height);
+    fn test_odi_manhattan_chart_draw_ui() {
+        let app = app_with_match("cricbuzz_odi_second_innings.json", None);
         let mut ui_state = UiState::new(1);
+        ui_state.match_view = MatchView::Manhattan;
 
-        terminal
-            .draw(|mut f| draw_ui(&mut f, &app, &mut ui_state))
-            .unwrap();
+        // Over 5 ended with a wicket, "2 0 1 0 0 W ", which takes precedence over the
+        // powerplay the other overs are a part of
+        let height = 40;
+        let buffer = draw(&app, 125, height, &mut ui_state);
+        assert_eq!(buffer.get(2, height - 5).fg, Color::Red);
+        assert_eq!(buffer.get(6, height - 5).fg, Color::Yellow);
+        assert_eq!(buffer.get(10, height - 5).fg, Color::Yellow);
 
-        let out = terminal.backend().buffer().content().to_vec();
-        let out = format_backend(out, width);
+        assert_ui_snapshot!(&app, 125, height, &mut ui_state);
+    }
 
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
+    #[test]
+    fn test_test_post_toss_draw_ui() {
+        let app = app_with_match("cricbuzz_test_post_toss.json", None);
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
+    }
 
-        settings.bind(|| {
-            insta::assert_display_snapshot!(out);
-        });
+    #[test]
+    fn test_odi_pre_toss_draw_ui() {
+        let app = app_with_match("cricbuzz_odi_pre_toss.json", None);
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
     }
 
     #[test]
-    fn test_test_fourth_inngs_draw_ui() {
-        let mut app = App::default();
+    fn test_odi_innings_break_draw_ui() {
+        let app = app_with_match("cricbuzz_odi_innings_break.json", None);
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
+    }
 
-        let scrd_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_test_scorecard_fourth_innings.txt"
-        ))
-        .unwrap();
-        let json_data = fs::read_to_string(format!(
-            "{}{}",
-            TEST_FILES_PATH, "cricbuzz_test_fourth_innings.json"
-        ))
-        .unwrap();
-
-        let json: CricbuzzJson = serde_json::from_str(&json_data).unwrap();
-        let mut scorecard = vec![];
-        parse_scorecard_from_file(&scrd_data, &mut scorecard);
-        let match_short_name = "ENG vs NZ".to_string();
-        let api_link = "".to_string();
-        let match_id = 33806;
-
-        let match_info = create_match_info(match_short_name, match_id, api_link, json, scorecard);
+    #[test]
+    fn test_odi_complete_draw_ui() {
+        let app = app_with_match(
+            "cricbuzz_odi_complete.json",
+            Some("cricbuzz_odi_scorecard_second_innings.txt"),
+        );
+        assert_ui_snapshot!(&app, 125, 35, &mut UiState::new(1));
+    }
 
-        app.matches_info.push(match_info);
+    #[test]
+    fn test_browser_without_matches_draw_ui() {
+        let mut app = App::default();
+        app.browser = Some(vec![BrowserEntry {
+            short_name: "BAN vs SL".to_string(),
+            match_id: 36096,
+            description: "1st ODI, Sri Lanka tour of Bangladesh, 2021".to_string(),
+            status: BrowserStatus::Completed("Bangladesh won by 33 runs".to_string()),
+        }]);
+        // No match is live, so the browser is opened without any tabs
+        let mut ui_state = UiState::new(0);
+        ui_state.toggle_browser();
+
+        assert_ui_snapshot!(&app, 125, 12, &mut ui_state);
+    }
 
-        let width = 125;
-        let height = 35;
-        let mut terminal = get_terminal(width, height);
+    #[test]
+    fn test_scorecard_scroll() {
         let mut ui_state = UiState::new(1);
 
-        terminal
-            .draw(|mut f| draw_ui(&mut f, &app, &mut ui_state))
-            .unwrap();
-
-        let out = terminal.backend().buffer().content().to_vec();
-        let out = format_backend(out, width);
-
-        let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(SNAPSHOTS_PATH);
+        // Scrolling before the scorecard is drawn does not underflow
+        ui_state.add_scrd_scroll(1);
+        assert_eq!(ui_state.current_scroll_value(), 0);
+
+        ui_state.scrd_scroll[0].1 = 4;
+        ui_state.add_scrd_scroll(1);
+        ui_state.add_scrd_scroll(1);
+        ui_state.add_scrd_scroll(1);
+        assert_eq!(ui_state.current_scroll_value(), 2);
+        ui_state.sub_scrd_scroll(5);
+        assert_eq!(ui_state.current_scroll_value(), 0);
+    }
 
-        settings.bind(|| {
-            insta::assert_display_snapshot!(out);
-        });
+    #[test]
+    fn test_format_start_time() {
+        assert_eq!(
+            format_start_time(1621753200000, &chrono::Utc),
+            "Sun 23 May 07:00"
+        );
     }
 }
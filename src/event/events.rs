@@ -1,12 +1,14 @@
 use crate::event::Key;
 use crossterm::event;
-use std::{sync::mpsc, thread, time::Duration};
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
 
 #[derive(Debug, Clone, Copy)]
 /// Configuration for event handling.
 pub struct EventConfig {
-    /// The key that is used to exit the application.
-    pub exit_key: Key,
     /// The tick rate at which the application will sent an tick event.
     pub tick_rate: Duration,
 }
@@ -14,7 +16,6 @@ pub struct EventConfig {
 impl Default for EventConfig {
     fn default() -> EventConfig {
         EventConfig {
-            exit_key: Key::Ctrl('c'),
             tick_rate: Duration::from_millis(250),
         }
     }
@@ -26,6 +27,8 @@ pub enum Event<I> {
     Input(I),
     /// An tick event occurred.
     Tick,
+    /// A background refresh of the match details finished.
+    Refreshed,
 }
 
 /// A small event handler that wrap crossterm input and tick event. Each event
@@ -33,7 +36,7 @@ pub enum Event<I> {
 pub struct Events {
     rx: mpsc::Receiver<Event<Key>>,
     // Need to be kept around to prevent disposing the sender side.
-    _tx: mpsc::Sender<Event<Key>>,
+    tx: mpsc::Sender<Event<Key>>,
 }
 
 impl Events {
@@ -41,7 +44,6 @@ impl Events {
     pub fn new(tick_rate: u64) -> Events {
         Events::with_config(EventConfig {
             tick_rate: Duration::from_millis(tick_rate),
-            ..Default::default()
         })
     }
 
@@ -51,9 +53,11 @@ impl Events {
 
         let event_tx = tx.clone();
         thread::spawn(move || {
+            let mut last_tick = Instant::now();
             loop {
-                // poll for tick rate duration, if no event, sent tick event.
-                if event::poll(config.tick_rate).unwrap() {
+                // poll for the time left until the next tick, if no event, sent tick event.
+                let timeout = config.tick_rate.saturating_sub(last_tick.elapsed());
+                if event::poll(timeout).unwrap() {
                     if let event::Event::Key(key) = event::read().unwrap() {
                         let key = Key::from(key);
 
@@ -61,11 +65,19 @@ impl Events {
                     }
                 }
 
-                event_tx.send(Event::Tick).unwrap();
+                if last_tick.elapsed() >= config.tick_rate {
+                    event_tx.send(Event::Tick).unwrap();
+                    last_tick = Instant::now();
+                }
             }
         });
 
-        Events { rx, _tx: tx }
+        Events { rx, tx }
+    }
+
+    /// Returns a sender through which other tasks can push events to the handler.
+    pub fn sender(&self) -> mpsc::Sender<Event<Key>> {
+        self.tx.clone()
     }
 
     /// Attempts to read an event.
@@ -1,5 +1,6 @@
 use crossterm::event;
-use std::fmt;
+use serde::{Deserialize, Deserializer};
+use std::{fmt, str::FromStr};
 
 /// Represents an key.
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
@@ -91,6 +92,73 @@ impl fmt::Display for Key {
     }
 }
 
+impl FromStr for Key {
+    type Err = String;
+
+    /// Parses a key as written in the config file. Eg. "q", "G", "ctrl+c", "alt+x", "left",
+    /// "pagedown", "space" or "f5". Names are case insensitive, single characters are not.
+    fn from_str(s: &str) -> Result<Key, String> {
+        let s = s.trim();
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::Char(c));
+        }
+
+        let lower = s.to_lowercase();
+        if let Some((modifier, rest)) = lower.split_once('+') {
+            let c = match rest {
+                "space" => ' ',
+                _ => {
+                    let mut chars = rest.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => c,
+                        _ => return Err(format!("unknown key \"{}\"", s)),
+                    }
+                }
+            };
+            return match modifier {
+                "ctrl" => Ok(Key::Ctrl(c)),
+                "alt" => Ok(Key::Alt(c)),
+                _ => Err(format!("unknown modifier in \"{}\"", s)),
+            };
+        }
+
+        let key = match lower.as_str() {
+            "enter" => Key::Enter,
+            "tab" => Key::Tab,
+            "backspace" => Key::Backspace,
+            "esc" => Key::Esc,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "ins" | "insert" => Key::Ins,
+            "delete" => Key::Delete,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            "space" => Key::Char(' '),
+            f => match f.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n) if n <= 12 => Key::from_f(n),
+                _ => return Err(format!("unknown key \"{}\"", s)),
+            },
+        };
+        Ok(key)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Key, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 impl From<event::KeyEvent> for Key {
     fn from(key_event: event::KeyEvent) -> Self {
         match key_event {
@@ -178,3 +246,21 @@ impl From<event::KeyEvent> for Key {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::event::Key;
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!("q".parse(), Ok(Key::Char('q')));
+        assert_eq!("G".parse(), Ok(Key::Char('G')));
+        assert_eq!("Ctrl+c".parse(), Ok(Key::Ctrl('c')));
+        assert_eq!("alt+space".parse(), Ok(Key::Alt(' ')));
+        assert_eq!("PageDown".parse(), Ok(Key::PageDown));
+        assert_eq!("f5".parse(), Ok(Key::F5));
+        assert!("f13".parse::<Key>().is_err());
+        assert!("shift+a".parse::<Key>().is_err());
+        assert!("ctrl+ab".parse::<Key>().is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! # Key Map
+//!
+//! Maps the keys pressed to the actions they trigger. Every action has vim-style keys in
+//! addition to the arrow keys by default, and any of them can be remapped in the `[keys]`
+//! table of the config file.
+//!
+//! ```toml
+//! [keys]
+//! quit = ["q", "ctrl+c", "esc"]
+//! refresh = ["f5"]
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::event::Key;
+
+/// Everything that can be done with a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    NextTab,
+    PrevTab,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    JumpToTop,
+    JumpToBottom,
+    Refresh,
+    TogglePane,
+    NextView,
+    NextInnings,
+    ToggleBrowser,
+    Open,
+    CloseTab,
+}
+
+impl Action {
+    /// Returns the action corresponding to its name in the config file
+    pub fn from_name(name: &str) -> Option<Action> {
+        let action = match name {
+            "quit" => Action::Quit,
+            "next_tab" => Action::NextTab,
+            "prev_tab" => Action::PrevTab,
+            "scroll_down" => Action::ScrollDown,
+            "scroll_up" => Action::ScrollUp,
+            "page_down" => Action::PageDown,
+            "page_up" => Action::PageUp,
+            "jump_to_top" => Action::JumpToTop,
+            "jump_to_bottom" => Action::JumpToBottom,
+            "refresh" => Action::Refresh,
+            "toggle_pane" => Action::TogglePane,
+            "next_view" => Action::NextView,
+            "next_innings" => Action::NextInnings,
+            "toggle_browser" => Action::ToggleBrowser,
+            "open" => Action::Open,
+            "close_tab" => Action::CloseTab,
+            _ => return None,
+        };
+        Some(action)
+    }
+}
+
+// Deserialized by hand, as the TOML parser cannot deserialize table keys into enums
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D>(deserializer: D) -> Result<Action, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        Action::from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown action \"{}\"", name)))
+    }
+}
+
+/// Keys bound to every action when the config file does not remap them
+const DEFAULT_BINDINGS: [(Action, &[Key]); 16] = [
+    (Action::Quit, &[Key::Char('q'), Key::Ctrl('c')]),
+    (Action::NextTab, &[Key::Right, Key::Char('l')]),
+    (Action::PrevTab, &[Key::Left, Key::Char('h')]),
+    (Action::ScrollDown, &[Key::Down, Key::Char('j')]),
+    (Action::ScrollUp, &[Key::Up, Key::Char('k')]),
+    (Action::PageDown, &[Key::PageDown, Key::Ctrl('d')]),
+    (Action::PageUp, &[Key::PageUp, Key::Ctrl('u')]),
+    (Action::JumpToTop, &[Key::Home, Key::Char('g')]),
+    (Action::JumpToBottom, &[Key::End, Key::Char('G')]),
+    (Action::Refresh, &[Key::Char('r')]),
+    (Action::TogglePane, &[Key::Tab]),
+    (Action::NextView, &[Key::Char('v')]),
+    (Action::NextInnings, &[Key::Char('i')]),
+    (Action::ToggleBrowser, &[Key::Char('b')]),
+    (Action::Open, &[Key::Enter]),
+    (Action::CloseTab, &[Key::Char('x')]),
+];
+
+/// Looks up the action bound to a key
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    actions: HashMap<Key, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> KeyBindings {
+        KeyBindings::new(&HashMap::new())
+    }
+}
+
+impl KeyBindings {
+    /// Returns the default bindings with the keys of some actions replaced. A key remapped
+    /// to another action no longer triggers its default action.
+    pub fn new(remapped: &HashMap<Action, Vec<Key>>) -> KeyBindings {
+        let mut actions = HashMap::new();
+
+        for (action, keys) in DEFAULT_BINDINGS.iter() {
+            if !remapped.contains_key(action) {
+                for key in keys.iter() {
+                    actions.insert(*key, *action);
+                }
+            }
+        }
+        for (action, keys) in remapped {
+            for key in keys {
+                actions.insert(*key, *action);
+            }
+        }
+
+        KeyBindings { actions }
+    }
+
+    /// Returns the action bound to a key, if any
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.actions.get(&key).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::event::{Action, Key, KeyBindings};
+
+    #[test]
+    fn test_remapped_key_bindings() {
+        let defaults = KeyBindings::default();
+        assert_eq!(defaults.action(Key::Char('j')), Some(Action::ScrollDown));
+        assert_eq!(defaults.action(Key::Down), Some(Action::ScrollDown));
+        assert_eq!(defaults.action(Key::Char('z')), None);
+
+        let mut remapped = HashMap::new();
+        remapped.insert(Action::Quit, vec![Key::Esc]);
+        remapped.insert(Action::Refresh, vec![Key::Char('j')]);
+        let bindings = KeyBindings::new(&remapped);
+
+        assert_eq!(bindings.action(Key::Esc), Some(Action::Quit));
+        assert_eq!(bindings.action(Key::Char('q')), None);
+        assert_eq!(bindings.action(Key::Char('j')), Some(Action::Refresh));
+        assert_eq!(bindings.action(Key::Char('k')), Some(Action::ScrollUp));
+    }
+}
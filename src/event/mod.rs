@@ -1,7 +1,9 @@
 mod events;
 mod key;
+mod keymap;
 
 pub use self::{
     events::{Event, Events},
     key::Key,
+    keymap::{Action, KeyBindings},
 };
@@ -0,0 +1,53 @@
+//! # cricket-rs
+//!
+//! Live cricket scores from Cricbuzz, along with the terminal UI and the JSON server built
+//! on top of them.
+//!
+//! Matches are fetched through a [`ScoreProvider`], of which [`CricbuzzProvider`] fetches
+//! from Cricbuzz. It lists the live matches, returns the details of a match as a
+//! [`CricbuzzJson`] and parses its scorecard into a [`MatchInningsInfo`] per innings.
+//!
+//! ```no_run
+//! use cricket_rs::{CricbuzzProvider, ScoreProvider};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), cricket_rs::ProviderError> {
+//!     let provider = CricbuzzProvider::new();
+//!
+//!     for live_match in provider.live_matches().await? {
+//!         let json = provider.match_info(live_match.match_id).await?;
+//!         let scorecard = provider.scorecard(live_match.match_id).await?;
+//!
+//!         println!("{} - {}", live_match.short_name, json.result_summary());
+//!         for innings in scorecard {
+//!             println!("{} ({})", innings.total, innings.total_details);
+//!         }
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`App`] keeps the details of several matches up to date, and is what the terminal UI
+//! and the `serve` subcommand of the `cricket-rs` binary show and serve.
+
+mod alert;
+mod app;
+mod cli;
+mod config;
+mod cricbuzz_api;
+mod diff;
+mod display;
+mod event;
+mod provider;
+mod server;
+#[cfg(test)]
+mod test_utils;
+
+pub use crate::{
+    app::{App, BatsmanInfo, BowlerInfo, FallOfWicket, MatchInfo, MatchInningsInfo, Overs, Stat},
+    cli::run_cli,
+    config::MatchFilter,
+    cricbuzz_api::CricbuzzJson,
+    provider::{CricbuzzProvider, ListedMatch, LiveMatch, ProviderError, ScoreProvider},
+};
@@ -0,0 +1,681 @@
+//! # Cricbuzz Provider
+//!
+//! Obtains live match information by scraping Cricbuzz's homepage, its commentary API
+//! and its scorecard HTML pages.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::{
+    app::{BatsmanInfo, BowlerInfo, FallOfWicket, MatchInningsInfo, Stat},
+    cricbuzz_api::CricbuzzJson,
+    provider::{recorder::Recorder, ListedMatch, LiveMatch, ProviderError, ScoreProvider},
+};
+
+const CRICBUZZ_URL: &str = "https://www.cricbuzz.com";
+const CRICBUZZ_MATCH_API: &str = "https://www.cricbuzz.com/api/cricket-match/commentary/";
+const CRICBUZZ_MATCH_SCORECARD_API: &str = "https://www.cricbuzz.com/api/html/cricket-scorecard/";
+
+/// Provider which fetches match details from Cricbuzz
+#[derive(Default)]
+pub struct CricbuzzProvider {
+    /// Connection client to send multiple requests and obtain updates
+    req_clt: Client,
+    /// Writes every fetched response to disk when set
+    recorder: Option<Recorder>,
+}
+
+impl CricbuzzProvider {
+    /// Returns a new Cricbuzz provider with its own connection client
+    pub fn new() -> CricbuzzProvider {
+        CricbuzzProvider {
+            req_clt: Client::new(),
+            recorder: None,
+        }
+    }
+
+    /// Returns a new Cricbuzz provider which records every fetched response
+    pub fn new_with_recorder(recorder: Recorder) -> CricbuzzProvider {
+        CricbuzzProvider {
+            req_clt: Client::new(),
+            recorder: Some(recorder),
+        }
+    }
+}
+
+#[async_trait]
+impl ScoreProvider for CricbuzzProvider {
+    async fn live_matches(&self) -> Result<Vec<LiveMatch>, ProviderError> {
+        let match_name_id =
+            get_all_live_matches_id_and_short_name(&self.req_clt, self.recorder.as_ref()).await?;
+
+        let live_matches: Vec<LiveMatch> = match_name_id
+            .into_iter()
+            .filter_map(|(short_name, id)| {
+                id.parse().ok().map(|match_id| LiveMatch {
+                    short_name,
+                    match_id,
+                })
+            })
+            .collect();
+
+        // Live matches are listed on every refresh, which starts a new frame of the recording
+        if let Some(recorder) = &self.recorder {
+            let ids: Vec<u32> = live_matches.iter().map(|lm| lm.match_id).collect();
+            recorder.start_frame(&ids);
+        }
+
+        Ok(live_matches)
+    }
+
+    async fn listed_matches(&self) -> Result<Vec<ListedMatch>, ProviderError> {
+        let resp_html = get_homepage(&self.req_clt, self.recorder.as_ref()).await?;
+        let mut listed = vec![];
+        parse_listed_matches(&resp_html, &mut listed)?;
+
+        Ok(listed
+            .into_iter()
+            .filter_map(|(short_name, id, status)| {
+                id.parse().ok().map(|match_id| ListedMatch {
+                    short_name,
+                    match_id,
+                    status,
+                })
+            })
+            .collect())
+    }
+
+    async fn match_info(&self, match_id: u32) -> Result<CricbuzzJson, ProviderError> {
+        get_match_info_from_id(&self.req_clt, self.recorder.as_ref(), match_id).await
+    }
+
+    async fn scorecard(&self, match_id: u32) -> Result<Vec<MatchInningsInfo>, ProviderError> {
+        let mut scorecard = vec![];
+        prepare_scorecard(
+            &self.req_clt,
+            self.recorder.as_ref(),
+            match_id,
+            &mut scorecard,
+        )
+        .await?;
+        Ok(scorecard)
+    }
+
+    fn match_link(&self, match_id: u32) -> String {
+        format!("{}{}", CRICBUZZ_MATCH_API, match_id)
+    }
+
+    fn take_failure(&self) -> Option<ProviderError> {
+        self.recorder
+            .as_ref()
+            .and_then(Recorder::take_failure)
+            .map(ProviderError::from)
+    }
+}
+
+/// Sends a GET request and returns the body of a successful response
+async fn get_text(req_clt: &Client, url: &str) -> Result<String, ProviderError> {
+    let resp = req_clt.get(url).send().await?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(ProviderError::HttpStatus {
+            url: url.to_string(),
+            status: status.as_u16(),
+        });
+    }
+
+    Ok(resp.text().await?)
+}
+
+/// Helper function to obtain all currently live matches from Cricbuzz's homepage
+// TODO: Need to improve method of getting all matches
+async fn get_all_live_matches_id_and_short_name(
+    req_clt: &Client,
+    recorder: Option<&Recorder>,
+) -> Result<Vec<(String, String)>, ProviderError> {
+    let resp_html = get_homepage(req_clt, recorder).await?;
+    let mut match_id_name = vec![];
+
+    parse_all_live_matches_id_and_short_name(&resp_html, &mut match_id_name)?;
+
+    Ok(match_id_name)
+}
+
+/// Fetches the Cricbuzz homepage, which lists the current matches in its menu
+async fn get_homepage(
+    req_clt: &Client,
+    recorder: Option<&Recorder>,
+) -> Result<String, ProviderError> {
+    let resp_html = get_text(req_clt, CRICBUZZ_URL).await?;
+    if let Some(recorder) = recorder {
+        // Failures are kept by the recorder until the app reports them
+        let _ = recorder.record_live_matches(&resp_html);
+    }
+    Ok(resp_html)
+}
+
+/// Helper function which scrapes match IDs and short names of the live matches from the
+/// Cricbuzz homepage
+///
+/// # Arguments
+/// * `html` - The HTML page
+/// * `match_id_name` - A vector containing a tuple of match short name and ID
+fn parse_all_live_matches_id_and_short_name(
+    html: &str,
+    match_id_name: &mut Vec<(String, String)>,
+) -> Result<(), ProviderError> {
+    let mut listed = vec![];
+    parse_listed_matches(html, &mut listed)?;
+
+    match_id_name.extend(
+        listed
+            .into_iter()
+            .filter(|(_, _, status)| status == "Live")
+            .map(|(short_name, id, _)| (short_name, id)),
+    );
+
+    Ok(())
+}
+
+/// Helper function which scrapes every match in the menu of the Cricbuzz homepage
+///
+/// # Arguments
+/// * `html` - The HTML page
+/// * `listed` - A vector containing a tuple of match short name, ID and status
+fn parse_listed_matches(
+    html: &str,
+    listed: &mut Vec<(String, String, String)>,
+) -> Result<(), ProviderError> {
+    let doc = Html::parse_document(html);
+
+    /* These unwraps will not panic */
+    let nav_sel = Selector::parse("nav.cb-mat-mnu").unwrap();
+    let sel_a = Selector::parse("a").unwrap();
+
+    let nav = doc
+        .select(&nav_sel)
+        .next()
+        .ok_or_else(|| ProviderError::Layout("Matches menu not found on homepage".to_string()))?;
+
+    for link in nav.select(&sel_a) {
+        let text = element_text(&link);
+        if text.is_empty() || text.eq("MATCHES") {
+            continue;
+        }
+
+        // Entries look like "NED vs SCO - SCO Won"
+        if let (Some((short_name, status)), Some(href)) =
+            (text.split_once('-'), link.value().attr("href"))
+        {
+            // Links look like "/live-cricket-scores/36096/ban-vs-sl-1st-odi"
+            let id = href
+                .split('/')
+                .nth(2)
+                .ok_or_else(|| ProviderError::Layout(format!("Unexpected match link {}", href)))?;
+            listed.push((
+                short_name.trim().to_string(),
+                id.to_string(),
+                status.trim().to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Obtains match information from the Cricbuzz API using the match ID
+async fn get_match_info_from_id(
+    req_clt: &Client,
+    recorder: Option<&Recorder>,
+    match_id: u32,
+) -> Result<CricbuzzJson, ProviderError> {
+    let resp = get_text(req_clt, &format!("{}{}", CRICBUZZ_MATCH_API, match_id)).await?;
+    if let Some(recorder) = recorder {
+        let _ = recorder.record_match_info(match_id, &resp);
+    }
+
+    Ok(CricbuzzJson::from_json_str(&resp)?)
+}
+
+/// Helper function to parse and structure scorecard data from the HTML page
+async fn prepare_scorecard(
+    req_clt: &Client,
+    recorder: Option<&Recorder>,
+    id: u32,
+    scorecard: &mut Vec<MatchInningsInfo>,
+) -> Result<(), ProviderError> {
+    let resp_html = get_text(req_clt, &format!("{}{}", CRICBUZZ_MATCH_SCORECARD_API, id)).await?;
+    if let Some(recorder) = recorder {
+        let _ = recorder.record_scorecard(id, &resp_html);
+    }
+
+    parse_scorecard(&resp_html, scorecard)
+}
+
+/// Parse scorecard data and structure it. Fails if the page holds no innings at all.
+pub fn parse_scorecard(
+    html: &str,
+    scorecard: &mut Vec<MatchInningsInfo>,
+) -> Result<(), ProviderError> {
+    let doc = Html::parse_document(html);
+    let num_innings = scorecard.len();
+
+    for ino in 1..5 {
+        // This unwrap will not panic
+        let inngs_sel = Selector::parse(format!("div[id=\"innings_{}\"]", ino).as_str()).unwrap();
+        if let Some(div) = doc.select(&inngs_sel).next() {
+            populate_innings_info(&div, scorecard);
+        }
+    }
+
+    if scorecard.len() == num_innings {
+        return Err(ProviderError::Layout(
+            "No innings found on scorecard".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Helper function to build structured innings information of a match
+///
+/// # Arguments
+///
+/// * `div` - Refers to the div element in the HTML page containing the innings information
+/// * `scorecard` - Vector of all innings information
+fn populate_innings_info(div: &ElementRef, scorecard: &mut Vec<MatchInningsInfo>) {
+    // These unwraps will never panic
+    let sel_scrd_items = Selector::parse("div.cb-scrd-itms").unwrap();
+    let sel_div = Selector::parse("div").unwrap();
+    let sel_a = Selector::parse("a").unwrap();
+    let sel_fow = Selector::parse("div.cb-col-rt").unwrap();
+    let sel_span = Selector::parse("span").unwrap();
+
+    let mut match_inngs_info = MatchInningsInfo::default();
+
+    for inner_div in div.select(&sel_scrd_items) {
+        // Check for batsman or bowler scorcard info
+        let num_child_div = inner_div.select(&sel_div).count();
+        if num_child_div == 7 {
+            // This is for batsman info
+            let mut bat_info = BatsmanInfo::default();
+            let mut divs = inner_div.select(&sel_div);
+            if let Some(bat_name_link) = divs.next() {
+                bat_info.name = bat_name_link
+                    .text()
+                    .collect::<Vec<&str>>()
+                    .concat()
+                    .trim()
+                    .to_string();
+            }
+
+            if let Some(div) = divs.next() {
+                bat_info.status = div
+                    .text()
+                    .collect::<Vec<&str>>()
+                    .concat()
+                    .trim()
+                    .to_string()
+            }
+
+            if let Some(div) = divs.next() {
+                bat_info.runs = Stat::parse(&div.inner_html());
+            }
+
+            if let Some(div) = divs.next() {
+                bat_info.balls = Stat::parse(&div.inner_html());
+            }
+            if let Some(div) = divs.next() {
+                bat_info.fours = Stat::parse(&div.inner_html());
+            }
+            if let Some(div) = divs.next() {
+                bat_info.sixes = Stat::parse(&div.inner_html());
+            }
+            if let Some(div) = divs.next() {
+                bat_info.strike_rate = Stat::parse(&div.inner_html());
+            }
+
+            match_inngs_info.batsman_details.push(bat_info);
+        } else if num_child_div == 8 {
+            // This is for bowler info
+            let mut bowl_info = BowlerInfo::default();
+            let mut divs = inner_div.select(&sel_div);
+            if let Some(bowl_name_link) = divs.next() {
+                bowl_info.name = bowl_name_link
+                    .text()
+                    .collect::<Vec<&str>>()
+                    .concat()
+                    .trim()
+                    .to_string();
+            }
+
+            if let Some(div) = divs.next() {
+                bowl_info.overs = Stat::parse(&div.inner_html());
+            }
+
+            if let Some(div) = divs.next() {
+                bowl_info.maidens = Stat::parse(&div.inner_html());
+            }
+
+            if let Some(div) = divs.next() {
+                bowl_info.runs = Stat::parse(&div.inner_html());
+            }
+
+            if let Some(div) = divs.next() {
+                bowl_info.wickets = Stat::parse(&div.inner_html());
+            }
+
+            if let Some(div) = divs.next() {
+                bowl_info.no_balls = Stat::parse(&div.inner_html());
+            }
+
+            if let Some(div) = divs.next() {
+                bowl_info.wides = Stat::parse(&div.inner_html());
+            }
+
+            if let Some(div) = divs.next() {
+                bowl_info.economy = Stat::parse(&div.inner_html());
+            }
+
+            match_inngs_info.bowler_details.push(bowl_info);
+        } else if num_child_div == 3 {
+            // This is for the extras or the innings total
+            let texts = inner_div
+                .select(&sel_div)
+                .map(|d| element_text(&d).replace('\u{a0}', " "))
+                .collect::<Vec<String>>();
+            let details = texts[2]
+                .trim()
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .to_string();
+
+            if texts[0] == "Extras" {
+                match_inngs_info.extras = Stat::parse(&texts[1]);
+                match_inngs_info.extras_details = details;
+            } else if texts[0] == "Total" {
+                match_inngs_info.total = Stat::parse(&texts[1]);
+                match_inngs_info.total_details = details;
+            }
+        } else if num_child_div == 2 {
+            // This is for the batsmen who are yet to bat or did not bat
+            let mut divs = inner_div.select(&sel_div);
+            if let (Some(label), Some(names)) = (divs.next(), divs.next()) {
+                let label = element_text(&label);
+                if label == "Yet to Bat" || label == "Did not Bat" {
+                    match_inngs_info.yet_to_bat =
+                        names.select(&sel_a).map(|a| element_text(&a)).collect();
+                }
+            }
+        }
+    }
+
+    if let Some(fow_div) = div.select(&sel_fow).next() {
+        match_inngs_info.fall_of_wickets = fow_div
+            .select(&sel_span)
+            .filter_map(|span| parse_fall_of_wicket(&element_text(&span)))
+            .collect();
+    }
+
+    scorecard.push(match_inngs_info);
+}
+
+/// Returns the trimmed text content of an element
+fn element_text(element: &ElementRef) -> String {
+    element
+        .text()
+        .collect::<Vec<&str>>()
+        .concat()
+        .trim()
+        .to_string()
+}
+
+/// Parses the fall of a wicket. Eg. "30-1 (Danushka Gunathilaka, 4.6),"
+fn parse_fall_of_wicket(text: &str) -> Option<FallOfWicket> {
+    let text = text.trim().trim_end_matches(',').trim_end();
+    let (score_wkt, rest) = text.split_once('(')?;
+    let (score, wicket) = score_wkt.trim().split_once('-')?;
+    let (batsman, overs) = rest.trim_end_matches(')').rsplit_once(',')?;
+
+    Some(FallOfWicket {
+        score: Stat::parse(score),
+        wicket: Stat::parse(wicket),
+        batsman: batsman.trim().to_string(),
+        overs: Stat::parse(overs),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        provider::{
+            cricbuzz::{
+                parse_all_live_matches_id_and_short_name, parse_fall_of_wicket,
+                parse_listed_matches, parse_scorecard,
+            },
+            ProviderError,
+        },
+        test_utils::{load_scorecard, read_test_file},
+    };
+
+    // Path is relative to where the testfile is present
+    const SNAPSHOTS_PATH: &str = "../../tests/snapshots";
+
+    #[test]
+    fn test_parse_all_live_matches_id_and_short_name_four_live_matches() {
+        let html = read_test_file("cricbuzz_home_four_live.txt");
+
+        let res_match_id_name: Vec<(String, String)> = vec![
+            ("KENT vs GLAM".to_string(), "33238".to_string()),
+            ("HAM vs LEIC".to_string(), "33243".to_string()),
+            ("SUR vs MDX".to_string(), "33253".to_string()),
+            ("GLOUCS vs SOM".to_string(), "33248".to_string()),
+        ];
+        let mut match_id_name = vec![];
+
+        parse_all_live_matches_id_and_short_name(&html, &mut match_id_name).unwrap();
+
+        assert_eq!(res_match_id_name, match_id_name);
+    }
+
+    #[test]
+    fn test_parse_all_live_matches_id_and_short_name_no_live_matches() {
+        let html = read_test_file("cricbuzz_home_no_live.txt");
+
+        let res_match_id_name: Vec<(String, String)> = vec![];
+        let mut match_id_name = vec![];
+
+        parse_all_live_matches_id_and_short_name(&html, &mut match_id_name).unwrap();
+
+        assert_eq!(res_match_id_name, match_id_name);
+    }
+
+    #[test]
+    fn test_parse_listed_matches() {
+        let html = read_test_file("cricbuzz_home_no_live.txt");
+
+        let mut listed = vec![];
+        parse_listed_matches(&html, &mut listed).unwrap();
+
+        assert_eq!(listed.len(), 5);
+        assert_eq!(
+            listed[0],
+            (
+                "NED vs SCO".to_string(),
+                "36092".to_string(),
+                "SCO Won".to_string()
+            )
+        );
+        assert_eq!(listed[1].2, "Break");
+    }
+
+    #[test]
+    fn test_parse_all_live_matches_id_and_short_name_layout_changed() {
+        let mut match_id_name = vec![];
+        let res = parse_all_live_matches_id_and_short_name("<html></html>", &mut match_id_name);
+
+        assert!(matches!(res, Err(ProviderError::Layout(_))));
+    }
+
+    /// Returns a scorecard page holding a single innings with the given rows, followed by
+    /// the fall of wickets if any
+    fn innings_html(rows: &[&str], fall_of_wickets: Option<&str>) -> String {
+        let rows: String = rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "<div class=\"cb-col cb-col-100 cb-scrd-itms\">{}</div>",
+                    row
+                )
+            })
+            .collect();
+        let fow = fall_of_wickets
+            .map(|fow| format!("<div class=\"cb-col cb-col-100 cb-col-rt\">{}</div>", fow))
+            .unwrap_or_default();
+        format!(
+            "<html><body><div id=\"innings_1\">{}{}</div></body></html>",
+            rows, fow
+        )
+    }
+
+    const EXTRAS_ROW: &str = "<div>Extras</div><div> 7 </div>\
+        <div>&nbsp;(b 1, lb 6, w 0, nb 0, p 0)</div>";
+
+    #[test]
+    fn test_parse_fall_of_wicket() {
+        let fow = parse_fall_of_wicket("30-1 (Danushka Gunathilaka, 4.6),").unwrap();
+        assert_eq!(fow.score.value, 30);
+        assert_eq!(fow.wicket.value, 1);
+        assert_eq!(fow.batsman, "Danushka Gunathilaka");
+        assert_eq!(fow.overs.value.total_balls(), 30);
+        assert_eq!(fow.to_string(), "30-1 (Danushka Gunathilaka, 4.6)");
+
+        // The last wicket is not followed by a comma
+        let fow = parse_fall_of_wicket(" 224-10 (Mustafizur Rahman, 48.1) ").unwrap();
+        assert_eq!((fow.score.value, fow.wicket.value), (224, 10));
+        assert_eq!(fow.overs.text, "48.1");
+
+        assert_eq!(parse_fall_of_wicket(""), None);
+        assert_eq!(parse_fall_of_wicket("30-1"), None);
+        assert_eq!(parse_fall_of_wicket("30 (Danushka Gunathilaka, 4.6)"), None);
+        assert_eq!(parse_fall_of_wicket("30-1 (Danushka Gunathilaka)"), None);
+    }
+
+    #[test]
+    fn test_parse_scorecard_extras_and_total() {
+        let html = innings_html(
+            &[
+                EXTRAS_ROW,
+                "<div>Total</div><div> 195 </div><div> &nbsp;(8 wkts, 59.5 Ov) </div>",
+                "<div> Yet to Bat </div><div><a href=\"/profiles/8560\">Matthew Quinn</a>\
+                 <a href=\"/profiles/8561\">Sam Cook</a></div>",
+            ],
+            None,
+        );
+        let mut scorecard = vec![];
+        parse_scorecard(&html, &mut scorecard).unwrap();
+
+        assert_eq!(scorecard.len(), 1);
+        let inns = &scorecard[0];
+        assert_eq!(inns.extras.value, 7);
+        assert_eq!(inns.extras_details, "b 1, lb 6, w 0, nb 0, p 0");
+        assert_eq!(inns.total.value, 195);
+        assert_eq!(inns.total_details, "8 wkts, 59.5 Ov");
+        assert_eq!(inns.yet_to_bat, vec!["Matthew Quinn", "Sam Cook"]);
+        // The fall of wickets is missing from the page
+        assert!(inns.fall_of_wickets.is_empty());
+    }
+
+    #[test]
+    fn test_parse_scorecard_all_out() {
+        let fow: String = (1..=10)
+            .map(|wkt| {
+                format!(
+                    "<span>{}-{} (Batsman {}, {}.1), </span>",
+                    wkt * 10,
+                    wkt,
+                    wkt,
+                    wkt
+                )
+            })
+            .collect();
+        let html = innings_html(
+            &[
+                EXTRAS_ROW,
+                "<div>Total</div><div> 100 </div><div> &nbsp;(10.1 Ov) </div>",
+                "<div> Did not Bat </div><div> </div>",
+            ],
+            Some(&fow),
+        );
+        let mut scorecard = vec![];
+        parse_scorecard(&html, &mut scorecard).unwrap();
+
+        let inns = &scorecard[0];
+        assert_eq!(inns.total.value, 100);
+        assert_eq!(inns.total_details, "10.1 Ov");
+        // Every batsman batted
+        assert!(inns.yet_to_bat.is_empty());
+        assert_eq!(inns.fall_of_wickets.len(), 10);
+        assert_eq!(inns.fall_of_wickets[9].wicket.value, 10);
+        assert_eq!(inns.fall_of_wickets[9].batsman, "Batsman 10");
+    }
+
+    #[test]
+    fn test_parse_scorecard_layout_changed() {
+        let mut scorecard = vec![];
+        let res = parse_scorecard("<html><div id=\"innings\"></div></html>", &mut scorecard);
+
+        assert!(matches!(res, Err(ProviderError::Layout(_))));
+        assert!(scorecard.is_empty());
+    }
+
+    #[test]
+    fn test_parse_scorecard_one_innings() {
+        let scorecard = load_scorecard("cricbuzz_scorecard_one_innings.txt");
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_path(SNAPSHOTS_PATH);
+
+        settings.bind(|| {
+            insta::assert_debug_snapshot!(scorecard);
+        });
+    }
+
+    #[test]
+    fn test_parse_scorecard_two_innings() {
+        let scorecard = load_scorecard("cricbuzz_scorecard_two_innings.txt");
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_path(SNAPSHOTS_PATH);
+
+        settings.bind(|| {
+            insta::assert_debug_snapshot!(scorecard);
+        });
+    }
+
+    #[test]
+    fn test_parse_scorecard_three_innings() {
+        let scorecard = load_scorecard("cricbuzz_scorecard_three_innings.txt");
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_path(SNAPSHOTS_PATH);
+
+        settings.bind(|| {
+            insta::assert_debug_snapshot!(scorecard);
+        });
+    }
+
+    #[test]
+    fn test_parse_scorecard_four_innings() {
+        let scorecard = load_scorecard("cricbuzz_scorecard_four_innings.txt");
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_path(SNAPSHOTS_PATH);
+
+        settings.bind(|| {
+            insta::assert_debug_snapshot!(scorecard);
+        });
+    }
+}
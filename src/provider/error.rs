@@ -0,0 +1,70 @@
+//! # Provider Errors
+//!
+//! Everything that can go wrong while obtaining match details, so the app can tell a flaky
+//! network apart from Cricbuzz changing its pages.
+
+use std::{error::Error, fmt, io, time::Duration};
+
+/// Error returned by all the providers
+#[derive(Debug)]
+pub enum ProviderError {
+    /// The request could not be sent or its response could not be read
+    Network(reqwest::Error),
+    /// The server responded with an unsuccessful status code
+    HttpStatus { url: String, status: u16 },
+    /// A JSON response does not match the expected format anymore
+    Schema(serde_json::Error),
+    /// An HTML page does not have the expected layout anymore
+    Layout(String),
+    /// No response was received in time
+    Timeout(Duration),
+    /// Reading or writing recorded responses on disk failed
+    Io(io::Error),
+    /// The provider has no details for the request
+    Unavailable(String),
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProviderError::Network(e) => write!(f, "Network error: {}", e),
+            ProviderError::HttpStatus { url, status } => {
+                write!(f, "HTTP status {} from {}", status, url)
+            }
+            ProviderError::Schema(e) => write!(f, "Unexpected JSON format: {}", e),
+            ProviderError::Layout(what) => write!(f, "Unexpected page layout: {}", what),
+            ProviderError::Timeout(after) => write!(f, "Request timed out after {:?}", after),
+            ProviderError::Io(e) => write!(f, "I/O error: {}", e),
+            ProviderError::Unavailable(what) => write!(f, "{}", what),
+        }
+    }
+}
+
+impl Error for ProviderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ProviderError::Network(e) => Some(e),
+            ProviderError::Schema(e) => Some(e),
+            ProviderError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ProviderError {
+    fn from(e: reqwest::Error) -> ProviderError {
+        ProviderError::Network(e)
+    }
+}
+
+impl From<serde_json::Error> for ProviderError {
+    fn from(e: serde_json::Error) -> ProviderError {
+        ProviderError::Schema(e)
+    }
+}
+
+impl From<io::Error> for ProviderError {
+    fn from(e: io::Error) -> ProviderError {
+        ProviderError::Io(e)
+    }
+}
@@ -0,0 +1,75 @@
+//! # Provider
+//!
+//! The `provider` module defines where the live match data comes from.
+//! `App` only talks to a `ScoreProvider`, so alternate sources can be plugged in.
+
+use async_trait::async_trait;
+
+use crate::{app::MatchInningsInfo, cricbuzz_api::CricbuzzJson};
+
+pub mod cricbuzz;
+pub mod error;
+pub mod recorder;
+pub mod replay;
+
+pub use self::{
+    cricbuzz::CricbuzzProvider, error::ProviderError, recorder::Recorder, replay::ReplayProvider,
+};
+
+/// A match that is currently live
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveMatch {
+    /// Short form of the teams playing the match. Eg. IND vs NZ
+    pub short_name: String,
+    /// Number used by the provider to identify a match
+    pub match_id: u32,
+}
+
+/// A match listed by a provider, which may or may not be live
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedMatch {
+    /// Short form of the teams playing the match. Eg. IND vs NZ
+    pub short_name: String,
+    /// Number used by the provider to identify a match
+    pub match_id: u32,
+    /// Status shown along with the match. Eg. "Live", "Preview" or "NZ Won"
+    pub status: String,
+}
+
+/// A source of live match information
+#[async_trait]
+pub trait ScoreProvider: Send + Sync {
+    /// Returns all the matches that are currently live
+    async fn live_matches(&self) -> Result<Vec<LiveMatch>, ProviderError>;
+
+    /// Returns every match the provider lists, whether it is live, yet to start or has
+    /// finished. Providers which only know about live matches list just those.
+    async fn listed_matches(&self) -> Result<Vec<ListedMatch>, ProviderError> {
+        Ok(self
+            .live_matches()
+            .await?
+            .into_iter()
+            .map(|lm| ListedMatch {
+                short_name: lm.short_name,
+                match_id: lm.match_id,
+                status: "Live".to_string(),
+            })
+            .collect())
+    }
+
+    /// Returns the live details of a match, i.e. the score summary, batsmen, bowlers and
+    /// commentary. Providers other than Cricbuzz map their data onto the same model.
+    async fn match_info(&self, match_id: u32) -> Result<CricbuzzJson, ProviderError>;
+
+    /// Returns the scorecard of all the innings played in a match
+    async fn scorecard(&self, match_id: u32) -> Result<Vec<MatchInningsInfo>, ProviderError>;
+
+    /// Returns the link from where live statistics of a match are obtained
+    fn match_link(&self, match_id: u32) -> String;
+
+    /// Returns a failure which did not stop the provider from returning match details,
+    /// like failing to record a response. A failure is only returned once.
+    fn take_failure(&self) -> Option<ProviderError> {
+        None
+    }
+}
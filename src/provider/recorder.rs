@@ -0,0 +1,199 @@
+//! # Recorder
+//!
+//! Writes every raw response fetched by a provider to disk, so real-world payloads can be
+//! used as test fixtures or played back with the replay provider.
+//!
+//! Files are laid out the way the replay provider expects them. The homepage is written to
+//! the top-level directory and the matches which were live to a sub-directory per match.
+//! Every refresh starts a new frame, and the commentary and scorecard fetched in a frame
+//! share its name, so a failed fetch does not pair up the snapshots of different frames.
+//!
+//! ```text
+//! record/
+//! ├── 1621770589549_home.html
+//! └── 36096/
+//!     ├── 1621770589549.json
+//!     └── 1621770589549.html
+//! ```
+
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Records raw responses to timestamped files in a directory
+pub struct Recorder {
+    dir: PathBuf,
+    frame: Mutex<Frame>,
+    /// The first failure to write a file since the last one was taken to be reported
+    failure: Mutex<Option<io::Error>>,
+}
+
+/// The matches being recorded since the last refresh
+#[derive(Default)]
+struct Frame {
+    /// Milliseconds since the Unix epoch when the frame started, which names its files
+    name: u128,
+    /// Matches which were live when the frame started. Matches opened from the browser
+    /// are not live, so they are left out of the recording.
+    live_match_ids: HashSet<u32>,
+}
+
+impl Recorder {
+    /// Returns a new recorder writing to `dir`, creating the directory if needed
+    pub fn new(dir: &Path) -> io::Result<Recorder> {
+        fs::create_dir_all(dir)?;
+        Ok(Recorder {
+            dir: dir.to_path_buf(),
+            frame: Mutex::new(Frame::default()),
+            failure: Mutex::new(None),
+        })
+    }
+
+    /// Starts a new frame, recording the details of the matches which are live from now on
+    pub fn start_frame(&self, live_match_ids: &[u32]) {
+        let mut frame = self.frame.lock().unwrap();
+        // Frames started within the same millisecond still get names of their own
+        frame.name = timestamp().max(frame.name + 1);
+        frame.live_match_ids = live_match_ids.iter().copied().collect();
+    }
+
+    /// Records the homepage listing all the live matches
+    pub fn record_live_matches(&self, html: &str) -> io::Result<PathBuf> {
+        let path = self.dir.join(format!("{}_home.html", timestamp()));
+        self.keep_failure(fs::write(&path, html))?;
+        Ok(path)
+    }
+
+    /// Records the commentary JSON of a match, returning `None` if the match was not live
+    /// when the frame started
+    pub fn record_match_info(&self, match_id: u32, json: &str) -> io::Result<Option<PathBuf>> {
+        self.record_match_file(match_id, "json", json)
+    }
+
+    /// Records the scorecard HTML of a match, returning `None` if the match was not live
+    /// when the frame started
+    pub fn record_scorecard(&self, match_id: u32, html: &str) -> io::Result<Option<PathBuf>> {
+        self.record_match_file(match_id, "html", html)
+    }
+
+    /// Returns the first failure to record a response since the last call
+    pub fn take_failure(&self) -> Option<io::Error> {
+        self.failure.lock().unwrap().take()
+    }
+
+    fn record_match_file(
+        &self,
+        match_id: u32,
+        ext: &str,
+        contents: &str,
+    ) -> io::Result<Option<PathBuf>> {
+        let name = {
+            let frame = self.frame.lock().unwrap();
+            if !frame.live_match_ids.contains(&match_id) {
+                return Ok(None);
+            }
+            frame.name
+        };
+
+        let match_dir = self.dir.join(match_id.to_string());
+        let path = match_dir.join(format!("{}.{}", name, ext));
+        self.keep_failure(fs::create_dir_all(&match_dir).and_then(|_| fs::write(&path, contents)))?;
+        Ok(Some(path))
+    }
+
+    /// Keeps the first failure until it is taken to be reported, since failing to record
+    /// should not stop the app from showing live scores
+    fn keep_failure(&self, res: io::Result<()>) -> io::Result<()> {
+        if let Err(e) = &res {
+            let mut failure = self.failure.lock().unwrap();
+            if failure.is_none() {
+                *failure = Some(io::Error::new(
+                    e.kind(),
+                    format!("Recording to {} failed: {}", self.dir.display(), e),
+                ));
+            }
+        }
+        res
+    }
+}
+
+/// Milliseconds since the Unix epoch, which keeps the file names in the order they were
+/// fetched
+fn timestamp() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::{
+        provider::{recorder::Recorder, replay::ReplayProvider, ScoreProvider},
+        test_utils::read_test_file,
+    };
+
+    #[tokio::test]
+    async fn test_recorded_session_can_be_replayed() {
+        let dir = env::temp_dir().join(format!("cricket-rs-record-{}", std::process::id()));
+        let recorder = Recorder::new(&dir).unwrap();
+
+        let json = read_test_file("cricbuzz_odi_first_innings.json");
+        let html = read_test_file("cricbuzz_odi_scorecard_first_innings.txt");
+
+        recorder.record_live_matches("<html></html>").unwrap();
+        recorder.start_frame(&[36096]);
+        let json_path = recorder.record_match_info(36096, &json).unwrap().unwrap();
+        let html_path = recorder.record_scorecard(36096, &html).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&json_path).unwrap(), json);
+        assert_eq!(json_path.file_stem(), html_path.file_stem());
+
+        // Matches opened from the browser are not live, so they are not recorded
+        assert_eq!(recorder.record_match_info(1, &json).unwrap(), None);
+
+        // The scorecard could not be fetched in the second frame
+        recorder.start_frame(&[36096]);
+        recorder.record_match_info(36096, &json).unwrap().unwrap();
+
+        let provider = ReplayProvider::new(&dir).unwrap();
+        let live = provider.live_matches().await.unwrap();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].match_id, 36096);
+        assert_eq!(provider.scorecard(36096).await.unwrap().len(), 1);
+        // The second frame is served along with the scorecard of the first
+        provider.live_matches().await.unwrap();
+        assert_eq!(provider.scorecard(36096).await.unwrap().len(), 1);
+        assert!(recorder.take_failure().is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_recording_failures_are_reported() {
+        let dir = env::temp_dir().join(format!("cricket-rs-record-fail-{}", std::process::id()));
+        let recorder = Recorder::new(&dir).unwrap();
+        // A file in place of the match directory makes recording the match fail
+        fs::write(dir.join("36096"), "").unwrap();
+
+        recorder.start_frame(&[36096]);
+        assert!(recorder.record_match_info(36096, "{}").is_err());
+        assert!(recorder.record_scorecard(36096, "").is_err());
+
+        let failure = recorder.take_failure().unwrap();
+        assert!(failure.to_string().starts_with("Recording to "));
+        assert!(recorder.take_failure().is_none());
+
+        // Failures after the first one was reported are reported too
+        assert!(recorder.record_match_info(36096, "{}").is_err());
+        assert!(recorder.take_failure().is_some());
+        assert!(recorder.take_failure().is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
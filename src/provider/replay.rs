@@ -0,0 +1,270 @@
+//! # Replay Provider
+//!
+//! Replays a recorded session from disk so the app can run without a network.
+//!
+//! The replay directory contains a sub-directory per match, named after the match ID.
+//! Each match directory holds the commentary JSON snapshots (`*.json`) and the scorecard
+//! HTML snapshots (`*.html` or `*.txt`). Snapshots are played in the order of their file
+//! names, one per tick, and the last snapshot is repeated once the replay runs out. A
+//! commentary snapshot is paired with the scorecard of the same name, or the previous
+//! scorecard if none was recorded along with it.
+//!
+//! ```text
+//! replay/
+//! └── 36096/
+//!     ├── 000.json
+//!     ├── 000.html
+//!     ├── 001.json
+//!     └── 001.html
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    app::MatchInningsInfo,
+    cricbuzz_api::CricbuzzJson,
+    provider::{cricbuzz::parse_scorecard, ListedMatch, LiveMatch, ProviderError, ScoreProvider},
+};
+
+/// All the recorded snapshots of a single match
+struct ReplayMatch {
+    match_id: u32,
+    snapshots: Vec<Snapshot>,
+}
+
+/// The commentary and scorecard of a match served in a frame
+struct Snapshot {
+    json_file: PathBuf,
+    scorecard_file: Option<PathBuf>,
+}
+
+/// Provider which plays back recorded match snapshots from a directory
+pub struct ReplayProvider {
+    matches: Vec<ReplayMatch>,
+    /// Number of times the live matches have been requested, i.e. the number of frames
+    /// that have been started
+    frames: AtomicUsize,
+}
+
+impl ReplayProvider {
+    /// Returns a new replay provider reading snapshots from `dir`
+    pub fn new(dir: &Path) -> io::Result<ReplayProvider> {
+        let mut matches = vec![];
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let match_id = match path.file_name().and_then(|n| n.to_str()) {
+                Some(name) => match name.parse() {
+                    Ok(id) => id,
+                    Err(_) => continue,
+                },
+                None => continue,
+            };
+
+            let mut json_files = vec![];
+            let mut scorecard_files = HashMap::new();
+            for file in fs::read_dir(&path)? {
+                let file = file?.path();
+                match file.extension().and_then(|e| e.to_str()) {
+                    Some("json") => json_files.push(file),
+                    Some("html") | Some("txt") => {
+                        scorecard_files.insert(file.with_extension(""), file);
+                    }
+                    _ => {}
+                }
+            }
+
+            if json_files.is_empty() {
+                continue;
+            }
+            json_files.sort();
+
+            let mut snapshots: Vec<Snapshot> = vec![];
+            for json_file in json_files {
+                let scorecard_file = scorecard_files
+                    .remove(&json_file.with_extension(""))
+                    .or_else(|| snapshots.last().and_then(|s| s.scorecard_file.clone()));
+                snapshots.push(Snapshot {
+                    json_file,
+                    scorecard_file,
+                });
+            }
+
+            matches.push(ReplayMatch {
+                match_id,
+                snapshots,
+            });
+        }
+
+        if matches.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No match snapshots found in {}", dir.display()),
+            ));
+        }
+        matches.sort_by_key(|m| m.match_id);
+
+        Ok(ReplayProvider {
+            matches,
+            frames: AtomicUsize::new(0),
+        })
+    }
+
+    /// Index of the snapshot to be served in the current frame
+    fn current_frame(&self) -> usize {
+        self.frames.load(Ordering::SeqCst).saturating_sub(1)
+    }
+
+    fn find_match(&self, match_id: u32) -> Result<&ReplayMatch, ProviderError> {
+        self.matches
+            .iter()
+            .find(|m| m.match_id == match_id)
+            .ok_or_else(|| {
+                ProviderError::Unavailable(format!("No snapshots recorded for match {}", match_id))
+            })
+    }
+
+    fn read_json(&self, rm: &ReplayMatch) -> Result<CricbuzzJson, ProviderError> {
+        let snapshot =
+            snapshot_for_frame(&rm.snapshots, self.current_frame()).ok_or_else(|| {
+                ProviderError::Unavailable("No commentary snapshots recorded".to_string())
+            })?;
+        Ok(CricbuzzJson::from_json_str(&fs::read_to_string(
+            &snapshot.json_file,
+        )?)?)
+    }
+
+    /// Returns every recorded match, named after the teams in the snapshot of the current
+    /// frame
+    fn recorded_matches(&self) -> Result<Vec<LiveMatch>, ProviderError> {
+        let mut matches = vec![];
+        for rm in &self.matches {
+            let json = self.read_json(rm)?;
+            matches.push(LiveMatch {
+                short_name: format!("{} vs {}", json.home_team_name(), json.away_team_name()),
+                match_id: rm.match_id,
+            });
+        }
+
+        Ok(matches)
+    }
+}
+
+/// Returns the snapshot of a frame, repeating the last snapshot once they run out
+fn snapshot_for_frame(snapshots: &[Snapshot], frame: usize) -> Option<&Snapshot> {
+    snapshots.get(frame).or_else(|| snapshots.last())
+}
+
+#[async_trait]
+impl ScoreProvider for ReplayProvider {
+    /// Every call starts a new frame, so the replay advances once per refresh
+    async fn live_matches(&self) -> Result<Vec<LiveMatch>, ProviderError> {
+        self.frames.fetch_add(1, Ordering::SeqCst);
+        self.recorded_matches()
+    }
+
+    /// Lists the same matches as `live_matches` without starting a new frame, so opening
+    /// the match browser does not advance the replay
+    async fn listed_matches(&self) -> Result<Vec<ListedMatch>, ProviderError> {
+        Ok(self
+            .recorded_matches()?
+            .into_iter()
+            .map(|lm| ListedMatch {
+                short_name: lm.short_name,
+                match_id: lm.match_id,
+                status: "Live".to_string(),
+            })
+            .collect())
+    }
+
+    async fn match_info(&self, match_id: u32) -> Result<CricbuzzJson, ProviderError> {
+        self.read_json(self.find_match(match_id)?)
+    }
+
+    async fn scorecard(&self, match_id: u32) -> Result<Vec<MatchInningsInfo>, ProviderError> {
+        let rm = self.find_match(match_id)?;
+        let mut scorecard = vec![];
+
+        if let Some(path) = snapshot_for_frame(&rm.snapshots, self.current_frame())
+            .and_then(|s| s.scorecard_file.as_ref())
+        {
+            parse_scorecard(&fs::read_to_string(path)?, &mut scorecard)?;
+        }
+
+        Ok(scorecard)
+    }
+
+    fn match_link(&self, match_id: u32) -> String {
+        self.find_match(match_id)
+            .ok()
+            .and_then(|rm| rm.snapshots[0].json_file.parent())
+            .map(|p| p.display().to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::{
+        provider::{replay::ReplayProvider, ScoreProvider},
+        test_utils::TEST_FILES_PATH,
+    };
+
+    #[tokio::test]
+    async fn test_replay_advances_one_snapshot_per_frame() {
+        let dir = env::temp_dir().join(format!("cricket-rs-replay-{}", std::process::id()));
+        let match_dir = dir.join("36096");
+        fs::create_dir_all(&match_dir).unwrap();
+
+        for (src, dst) in [
+            ("cricbuzz_odi_first_innings.json", "000.json"),
+            ("cricbuzz_odi_scorecard_first_innings.txt", "000.html"),
+            ("cricbuzz_odi_second_innings.json", "001.json"),
+            ("cricbuzz_odi_scorecard_second_innings.txt", "001.html"),
+        ] {
+            fs::copy(format!("{}{}", TEST_FILES_PATH, src), match_dir.join(dst)).unwrap();
+        }
+
+        let provider = ReplayProvider::new(&dir).unwrap();
+
+        let live = provider.live_matches().await.unwrap();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].short_name, "BAN vs SL");
+        let json = provider.match_info(36096).await.unwrap();
+        assert_eq!(json.miniscore.innings_id, 1);
+        assert_eq!(provider.scorecard(36096).await.unwrap().len(), 1);
+
+        // Listing the matches for the browser stays on the same frame
+        let listed = provider.listed_matches().await.unwrap();
+        assert_eq!(listed[0].status, "Live");
+        let json = provider.match_info(36096).await.unwrap();
+        assert_eq!(json.miniscore.innings_id, 1);
+
+        provider.live_matches().await.unwrap();
+        let json = provider.match_info(36096).await.unwrap();
+        assert_eq!(json.miniscore.innings_id, 2);
+        assert_eq!(provider.scorecard(36096).await.unwrap().len(), 2);
+
+        // The last snapshot is repeated once the replay runs out
+        provider.live_matches().await.unwrap();
+        let json = provider.match_info(36096).await.unwrap();
+        assert_eq!(json.miniscore.innings_id, 2);
+
+        assert!(provider.match_info(1).await.is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,311 @@
+//! # Server
+//!
+//! Headless mode started with the `serve` subcommand. The matches are refreshed in the
+//! background just like in the UI, and served as JSON so many clients can share a single
+//! poller instead of each of them fetching from Cricbuzz.
+//!
+//! | Endpoint                      | Response                                              |
+//! | ----------------------------- | ----------------------------------------------------- |
+//! | `GET /matches`                | Every match along with its miniscore                  |
+//! | `GET /matches/{id}`           | A single match along with its miniscore               |
+//! | `GET /matches/{id}/scorecard` | Batsmen, bowlers and fall of wickets of every innings |
+//! | `GET /events`                 | Server-Sent Events stream of what changed in matches  |
+//!
+//! Every refresh which changes the score, the batsmen at the crease or the status of a
+//! match pushes a `diff` event to the clients of `/events`, holding only what changed.
+//!
+//! ```text
+//! event:diff
+//! data:{"match_id":36096,"match_name":"BAN vs SL","status":"Sri Lanka need 212 runs"}
+//! ```
+//!
+//! Finished matches are served along with their result for an hour, after which they are
+//! dropped since nobody closes them like in the UI.
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::get,
+    Json, Router,
+};
+use futures::stream::{self, Stream};
+use serde::Serialize;
+use tokio::{
+    sync::{broadcast, RwLock},
+    time,
+};
+
+use crate::{
+    app::{fetch_update, App, MatchInfo},
+    cricbuzz_api::CricbuzzMiniscore,
+    diff::MatchDiff,
+};
+
+/// Diffs kept for clients which are slow to read them, older ones are dropped
+const DIFF_CHANNEL_CAPACITY: usize = 64;
+/// Time for which finished matches are served after their result
+const COMPLETED_MATCH_GRACE: Duration = Duration::from_secs(60 * 60);
+
+/// The app shared by the refresh loop and the request handlers
+type SharedApp = Arc<RwLock<App>>;
+
+/// State shared by the request handlers
+#[derive(Clone)]
+struct ServerState {
+    app: SharedApp,
+    /// Sends the diffs of every refresh to the clients of `/events`
+    diffs: broadcast::Sender<MatchDiff>,
+}
+
+/// A match along with its miniscore, which holds the live score, batsmen and bowlers
+#[derive(Serialize)]
+struct MatchResponse<'a> {
+    #[serde(flatten)]
+    info: &'a MatchInfo,
+    miniscore: &'a CricbuzzMiniscore,
+}
+
+impl<'a> From<&'a MatchInfo> for MatchResponse<'a> {
+    fn from(info: &'a MatchInfo) -> MatchResponse<'a> {
+        MatchResponse {
+            info,
+            miniscore: &info.cricbuzz_info.miniscore,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Serves the matches of the app on an address until the process is killed, refreshing
+/// them every `tick_rate` milliseconds, which has to be above zero
+pub async fn serve(
+    mut app: App,
+    addr: SocketAddr,
+    tick_rate: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    app.track_diffs();
+    let state = ServerState {
+        app: Arc::new(RwLock::new(app)),
+        diffs: broadcast::channel(DIFF_CHANNEL_CAPACITY).0,
+    };
+    tokio::spawn(refresh_loop(
+        state.clone(),
+        Duration::from_millis(tick_rate),
+    ));
+
+    let server = axum::Server::try_bind(&addr)?.serve(router(state).into_make_service());
+    eprintln!("Serving matches on http://{}", server.local_addr());
+    server.await?;
+
+    Ok(())
+}
+
+/// Returns the routes of the API
+fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/matches", get(list_matches))
+        .route("/matches/:id", get(get_match))
+        .route("/matches/:id/scorecard", get(get_scorecard))
+        .route("/events", get(stream_events))
+        .with_state(state)
+}
+
+/// Refreshes the matches of the app forever, pushing what changed to the clients of
+/// `/events`. The lock is only held while applying the update, so requests are answered
+/// while the details are being fetched.
+async fn refresh_loop(state: ServerState, tick_rate: Duration) {
+    let mut interval = time::interval(tick_rate);
+    // The first tick completes immediately, and the matches have just been fetched
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let (provider, permits, match_ids, known_ids) = {
+            let app = state.app.read().await;
+            (
+                app.provider(),
+                app.request_permits(),
+                app.refresh_match_ids(),
+                app.known_match_ids(),
+            )
+        };
+        let update = fetch_update(provider.as_ref(), &permits, match_ids, known_ids).await;
+
+        let diffs = {
+            let mut app = state.app.write().await;
+            app.apply_update(update);
+            app.close_completed_matches(COMPLETED_MATCH_GRACE);
+            // Alerts are only acted upon in the UI
+            app.take_alerts();
+            app.take_diffs()
+        };
+        for diff in diffs {
+            // Fails only when no client is listening
+            let _ = state.diffs.send(diff);
+        }
+    }
+}
+
+async fn list_matches(State(state): State<ServerState>) -> Response {
+    let app = state.app.read().await;
+    let matches: Vec<MatchResponse> = app.matches_info.iter().map(MatchResponse::from).collect();
+    Json(matches).into_response()
+}
+
+async fn get_match(State(state): State<ServerState>, Path(match_id): Path<u32>) -> Response {
+    let app = state.app.read().await;
+    match find_match(&app, match_id) {
+        Some(mi) => Json(MatchResponse::from(mi)).into_response(),
+        None => match_not_found(match_id),
+    }
+}
+
+async fn get_scorecard(State(state): State<ServerState>, Path(match_id): Path<u32>) -> Response {
+    let app = state.app.read().await;
+    match find_match(&app, match_id) {
+        Some(mi) => Json(&mi.scorecard).into_response(),
+        None => match_not_found(match_id),
+    }
+}
+
+async fn stream_events(
+    State(state): State<ServerState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = stream::unfold(state.diffs.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(diff) => {
+                    let event = Event::default().event("diff").json_data(diff);
+                    // Serialising plain structs to JSON will not fail
+                    return event.ok().map(|event| (Ok(event), rx));
+                }
+                // Clients which fell behind miss the oldest diffs
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+fn find_match(app: &App, match_id: u32) -> Option<&MatchInfo> {
+    app.matches_info
+        .iter()
+        .find(|mi| mi.cricbuzz_match_id == match_id)
+}
+
+fn match_not_found(match_id: u32) -> Response {
+    let body = ErrorResponse {
+        error: format!("No match with ID {}", match_id),
+    };
+    (StatusCode::NOT_FOUND, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc};
+
+    use serde_json::Value;
+    use tokio::sync::{broadcast, RwLock};
+
+    use crate::{
+        app::App,
+        diff::MatchDiff,
+        server::{router, ServerState, DIFF_CHANNEL_CAPACITY},
+        test_utils::load_match,
+    };
+
+    /// Serves an app holding the second innings of an ODI on a free port
+    fn spawn_server() -> (SocketAddr, ServerState) {
+        let mut app = App::default();
+        app.matches_info.push(load_match(
+            "cricbuzz_odi_second_innings.json",
+            Some("cricbuzz_odi_scorecard_second_innings.txt"),
+        ));
+
+        let state = ServerState {
+            app: Arc::new(RwLock::new(app)),
+            diffs: broadcast::channel(DIFF_CHANNEL_CAPACITY).0,
+        };
+        let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap())
+            .serve(router(state.clone()).into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        (addr, state)
+    }
+
+    async fn get(addr: SocketAddr, path: &str) -> (u16, Value) {
+        let res = reqwest::get(format!("http://{}{}", addr, path))
+            .await
+            .unwrap();
+        (res.status().as_u16(), res.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_serve_matches() {
+        let (addr, _) = spawn_server();
+
+        let (status, matches) = get(addr, "/matches").await;
+        assert_eq!(status, 200);
+        assert_eq!(matches.as_array().unwrap().len(), 1);
+        assert_eq!(matches[0]["match_short_name"], "BAN vs SL");
+        assert_eq!(matches[0]["cricbuzz_match_id"], 36096);
+
+        let (status, m) = get(addr, "/matches/36096").await;
+        assert_eq!(status, 200);
+        assert_eq!(m["miniscore"]["inningsId"], 2);
+        assert_eq!(
+            m["miniscore"]["matchScoreDetails"]["inningsScoreList"][0]["batTeamName"],
+            "SL"
+        );
+
+        let (status, scorecard) = get(addr, "/matches/36096/scorecard").await;
+        assert_eq!(status, 200);
+        assert_eq!(scorecard.as_array().unwrap().len(), 2);
+        assert!(scorecard[0]["batsman_details"][0]["runs"]["value"].is_u64());
+
+        let (status, err) = get(addr, "/matches/1").await;
+        assert_eq!(status, 404);
+        assert_eq!(err["error"], "No match with ID 1");
+    }
+
+    #[tokio::test]
+    async fn test_stream_events() {
+        let (addr, state) = spawn_server();
+
+        let mut res = reqwest::get(format!("http://{}/events", addr))
+            .await
+            .unwrap();
+        assert_eq!(res.headers()["content-type"], "text/event-stream");
+
+        state
+            .diffs
+            .send(MatchDiff {
+                match_id: 36096,
+                match_name: "BAN vs SL".to_string(),
+                score: None,
+                wicket: None,
+                batsmen: None,
+                status: Some("Sri Lanka need 212 runs".to_string()),
+            })
+            .unwrap();
+
+        let chunk = res.chunk().await.unwrap().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&chunk),
+            "event:diff\ndata:{\"match_id\":36096,\"match_name\":\"BAN vs SL\",\
+             \"status\":\"Sri Lanka need 212 runs\"}\n\n"
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Loading of the Cricbuzz responses in `tests/data` shared by the tests of every module
+
+use std::fs;
+
+use crate::{
+    app::{create_match_info, MatchInfo, MatchInningsInfo},
+    cricbuzz_api::CricbuzzJson,
+    provider::cricbuzz::parse_scorecard,
+};
+
+// Path is relative to where `cargo test` command is run
+pub const TEST_FILES_PATH: &str = "./tests/data/";
+
+/// Returns the contents of a file in the test data directory
+pub fn read_test_file(name: &str) -> String {
+    fs::read_to_string(format!("{}{}", TEST_FILES_PATH, name)).unwrap()
+}
+
+/// Parses the match details saved in a file in the test data directory
+pub fn load_json(name: &str) -> CricbuzzJson {
+    CricbuzzJson::from_json_str(&read_test_file(name)).unwrap()
+}
+
+/// Parses the scorecard page saved in a file in the test data directory
+pub fn load_scorecard(name: &str) -> Vec<MatchInningsInfo> {
+    let mut scorecard = vec![];
+    parse_scorecard(&read_test_file(name), &mut scorecard)
+        .expect("Scorecard test files hold at least one innings");
+    scorecard
+}
+
+/// Creates the info of the match saved in the test data directory, named after its teams.
+/// The scorecard is left empty when no file is given.
+pub fn load_match(json: &str, scorecard: Option<&str>) -> MatchInfo {
+    let json = load_json(json);
+    let header = &json.match_header;
+    let short_name = format!("{} vs {}", header.team1.short_name, header.team2.short_name);
+    let match_id = header.match_id;
+    let scorecard = scorecard.map(load_scorecard).unwrap_or_default();
+
+    create_match_info(short_name, match_id, "".to_string(), json, scorecard)
+}